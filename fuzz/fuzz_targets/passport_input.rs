@@ -0,0 +1,5 @@
+#![no_main]
+use libfuzzer_sys::fuzz_target;
+use modulitos_2020::FuzzTarget;
+
+fuzz_target!(|data: &[u8]| FuzzTarget::PassportInput.run(data));
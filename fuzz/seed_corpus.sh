@@ -0,0 +1,11 @@
+#!/bin/sh
+# Seeds each fuzz target's corpus with its day's example and puzzle inputs.
+set -eu
+cd "$(dirname "$0")"
+for target_day in receipts:01 policy_with_password:02 area:03 passport_input:04 \
+    seat_assignment:05 group:06 rule:07; do
+    target=${target_day%%:*}
+    day=${target_day##*:}
+    mkdir -p "corpus/$target"
+    cp ../inputs/day_"$day".*txt "corpus/$target/"
+done
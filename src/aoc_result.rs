@@ -1,15 +1,74 @@
+use serde::Serialize;
+use std::fmt;
+
 #[allow(clippy::doc_markdown)]
 /// Some AoC puzzles return a `u32`, while others return a `Vec<u32`.  In deciding to have all
 /// the puzzles run from a single application, it makes sense to harmonize these types to return
-/// to the caller.
-#[derive(Clone, Debug, Eq, PartialEq)]
+/// to the caller.  Each solver should return its natural type, rather than casting to one of
+/// these.
+///
+/// When serialized, only the inner value is written (eg: `876459`, `null`, `[1, 2]` or `"abc"`).
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+#[serde(untagged)]
 pub enum AocReturn {
     U32Item(u32),
     UsizeItem(usize),
     U64Item(u64),
+    I64Item(i64),
+    U128Item(u128),
     U32ItemOpt(Option<u32>),
     U32List(Vec<u32>),
     UsizeList(Vec<usize>),
+    /// Textual answers, like comma-joined labels, or letters rendered as a grid.
+    Text(String),
+}
+
+impl AocReturn {
+    /// The name of the type wrapped by this answer, for reporting alongside the answer.
+    pub fn type_name(&self) -> &'static str {
+        match self {
+            Self::U32Item(_) => "u32",
+            Self::UsizeItem(_) => "usize",
+            Self::U64Item(_) => "u64",
+            Self::I64Item(_) => "i64",
+            Self::U128Item(_) => "u128",
+            Self::U32ItemOpt(_) => "Option<u32>",
+            Self::U32List(_) => "Vec<u32>",
+            Self::UsizeList(_) => "Vec<usize>",
+            Self::Text(_) => "String",
+        }
+    }
+
+    /// Whether the solution found an answer: only an optional answer can be missing.
+    pub fn is_solved(&self) -> bool {
+        *self != Self::U32ItemOpt(None)
+    }
+}
+
+/// Displays just the answer, as it would be submitted.  This is the canonical rendering, which is
+/// also what known answers are compared against.  Lists are comma-separated, a missing answer is
+/// displayed as `none`, and text is displayed as is.
+impl fmt::Display for AocReturn {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fn join<T: ToString>(list: &[T]) -> String {
+            list.iter()
+                .map(ToString::to_string)
+                .collect::<Vec<String>>()
+                .join(",")
+        }
+        match self {
+            Self::U32Item(item) => write!(f, "{}", item),
+            Self::UsizeItem(item) => write!(f, "{}", item),
+            Self::U64Item(item) => write!(f, "{}", item),
+            Self::I64Item(item) => write!(f, "{}", item),
+            Self::U128Item(item) => write!(f, "{}", item),
+            Self::U32ItemOpt(Some(item)) => write!(f, "{}", item),
+            Self::U32ItemOpt(None) => write!(f, "none"),
+            Self::U32List(list) => write!(f, "{}", join(list)),
+            Self::UsizeList(list) => write!(f, "{}", join(list)),
+            Self::Text(text) => write!(f, "{}", text),
+        }
+    }
 }
 
 impl From<Option<u32>> for AocReturn {
@@ -30,6 +89,18 @@ impl From<u64> for AocReturn {
     }
 }
 
+impl From<i64> for AocReturn {
+    fn from(item: i64) -> Self {
+        Self::I64Item(item)
+    }
+}
+
+impl From<u128> for AocReturn {
+    fn from(item: u128) -> Self {
+        Self::U128Item(item)
+    }
+}
+
 impl From<usize> for AocReturn {
     fn from(item: usize) -> Self {
         Self::UsizeItem(item)
@@ -47,3 +118,56 @@ impl From<Vec<usize>> for AocReturn {
         Self::UsizeList(list)
     }
 }
+
+impl From<String> for AocReturn {
+    fn from(text: String) -> Self {
+        Self::Text(text)
+    }
+}
+
+impl From<&str> for AocReturn {
+    fn from(text: &str) -> Self {
+        Self::Text(text.to_string())
+    }
+}
+
+#[test]
+fn test_display() {
+    assert_eq!(AocReturn::from(Some(876459_u32)).to_string(), "876459");
+    assert_eq!(AocReturn::from(None::<u32>).to_string(), "none");
+    assert_eq!(AocReturn::from(5872458240_u64).to_string(), "5872458240");
+    assert_eq!(AocReturn::from(vec![1_usize, 2, 3]).to_string(), "1,2,3");
+    assert_eq!(AocReturn::from(-42_i64).to_string(), "-42");
+    assert_eq!(
+        AocReturn::from(u128::from(u64::MAX) * 2).to_string(),
+        "36893488147419103230"
+    );
+    assert_eq!(AocReturn::from("a,b,c").to_string(), "a,b,c");
+    assert_eq!(
+        AocReturn::from(String::from("#.\n.#")).to_string(),
+        "#.\n.#"
+    );
+}
+
+#[test]
+fn test_serialize() -> serde_json::Result<()> {
+    assert_eq!(
+        serde_json::to_string(&AocReturn::from(Some(876459_u32)))?,
+        "876459"
+    );
+    assert_eq!(
+        serde_json::to_string(&AocReturn::from(None::<u32>))?,
+        "null"
+    );
+    assert_eq!(
+        serde_json::to_string(&AocReturn::from(vec![1_u32, 2]))?,
+        "[1,2]"
+    );
+    assert_eq!(serde_json::to_string(&AocReturn::from(-42_i64))?, "-42");
+    assert_eq!(
+        serde_json::to_string(&AocReturn::from(u128::from(u64::MAX) * 2))?,
+        "36893488147419103230"
+    );
+    assert_eq!(serde_json::to_string(&AocReturn::from("abc"))?, "\"abc\"");
+    Ok(())
+}
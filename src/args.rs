@@ -1,14 +1,156 @@
+use crate::format::Format;
+use crate::runner::DayRange;
 use std::path::PathBuf;
 use structopt::StructOpt;
 
 #[derive(Debug, StructOpt)]
 #[structopt(name = "basic")]
 pub struct Args {
-    /// The AoC day (1-25).
-    pub day: usize,
-    /// The AoC day's question part (1-2).
-    pub part: usize,
-    /// Optional path to input file.  If not provided, data will be read from stdin.
+    /// The AoC day (1-25).  Required unless a subcommand is given.
+    pub day: Option<usize>,
+    /// The AoC day's question part (1-2).  Both parts are answered when omitted.
+    pub part: Option<usize>,
+    /// Optional path to input file, or `-` to read from stdin.  If not provided, the day's input
+    /// is found in the input directory.
     #[structopt(parse(from_os_str))]
     pub input_data_file: Option<PathBuf>,
+    /// The AoC year.  Defaults to 2020, and other years' inputs are found in a subdirectory of
+    /// the input directory named after the year (eg: `inputs/2019`).
+    #[structopt(long, global = true)]
+    pub year: Option<usize>,
+    /// Use the day's example input, rather than the puzzle input, when no input file is given.
+    #[structopt(long, global = true)]
+    pub example: bool,
+    /// Configuration file with defaults for these options.  Defaults to `aoc.toml`, falling back
+    /// to the user's `~/.config/aoc/config.toml`.
+    #[structopt(long, global = true, parse(from_os_str))]
+    pub config: Option<PathBuf>,
+    /// Directory holding each day's `day_XX.txt` puzzle input and `day_XX.example.txt` example.
+    /// Defaults to `inputs`.
+    #[structopt(long, global = true, parse(from_os_str))]
+    pub input_dir: Option<PathBuf>,
+    /// Machine-readable output format for answers: plain, json or csv.
+    #[structopt(long, global = true)]
+    pub format: Option<Format>,
+    /// The puzzles' website, which can be pointed elsewhere (eg: at a local server, for testing).
+    /// Defaults to `https://adventofcode.com`.
+    #[structopt(long, global = true)]
+    pub base_url: Option<String>,
+    /// File recording every computed and submitted answer.  Defaults to `history.json`.
+    #[structopt(long, global = true, parse(from_os_str))]
+    pub history: Option<PathBuf>,
+    /// File holding the website's session token, which is used unless `AOC_SESSION` is set.
+    /// Defaults to `.aoc_session`.
+    #[structopt(long, global = true, parse(from_os_str))]
+    pub session_file: Option<PathBuf>,
+    /// Shows the solvers' debug events on stderr, or their trace events too when repeated (`-vv`).
+    #[structopt(short, long, global = true, parse(from_occurrences))]
+    pub verbose: u8,
+    #[structopt(subcommand)]
+    pub command: Option<Command>,
+}
+
+#[derive(Debug, StructOpt)]
+pub enum Command {
+    /// Lists the days that have been implemented so far.
+    List,
+    /// Runs both parts of every implemented day against its input, and prints a summary.
+    All {
+        /// Optional day, or range of days, to run (eg: `3`, `1..=7`).  Defaults to every day.
+        days: Option<DayRange>,
+        /// The number of worker threads to spread the days across.  `0` uses one per cpu, and `1`
+        /// runs them one after another.
+        #[structopt(short = "j", long, default_value = "0")]
+        jobs: usize,
+    },
+    /// Runs a day's part repeatedly, and reports statistics on how long each phase took.
+    Bench {
+        /// Optional day, or range of days, to benchmark (eg: `7`, `1..=7`).  Defaults to every
+        /// implemented day.
+        days: Option<DayRange>,
+        /// The AoC day's question part (1-2).  Both parts are benchmarked when omitted.
+        part: Option<usize>,
+        /// Optional path to input file, when benchmarking a single day.  If not provided, each
+        /// day's input is found in the input directory.
+        #[structopt(parse(from_os_str))]
+        input_data_file: Option<PathBuf>,
+        /// How many times to run the solution.  Defaults to 10.
+        #[structopt(short = "n", long)]
+        iterations: Option<usize>,
+        /// Compares each median total time against the one saved in this baseline file, and fails
+        /// when any is slower by more than the threshold.
+        #[structopt(long, parse(from_os_str))]
+        baseline: Option<PathBuf>,
+        /// Saves the results to this baseline file, replacing any saved for the same day's part.
+        #[structopt(long, parse(from_os_str))]
+        save_baseline: Option<PathBuf>,
+        /// The percentage slowdown, against the baseline, that counts as a regression.
+        #[structopt(long, default_value = "10")]
+        threshold: f64,
+    },
+    /// Generates the module for a new day, and registers it.  Refuses to overwrite an existing day.
+    New {
+        /// The AoC day (1-25).  Defaults to the day after the last implemented day.
+        day: Option<usize>,
+        /// The title of the day's puzzle.
+        #[structopt(long, default_value = "TODO")]
+        title: String,
+    },
+    /// Downloads the puzzle inputs for a day, or range of days, into the input directory.  An input
+    /// that was already downloaded is never downloaded again.
+    Fetch {
+        /// The day, or range of days, to fetch (eg: `3`, `1..=7`).
+        days: DayRange,
+    },
+    /// Submits the answer for a day's part to the website, and reports whether it was right.  An
+    /// answer that was already judged to be wrong is never submitted again.
+    Submit {
+        /// The AoC day (1-25).
+        day: usize,
+        /// The AoC day's question part (1-2).
+        part: usize,
+        /// The answer to submit.  If not provided, it's computed from the day's input.
+        answer: Option<String>,
+    },
+    /// Lists the computed and submitted answers, along with the website's feedback.
+    History {
+        /// Only list this day's answers.
+        day: Option<usize>,
+        /// Only list this part's answers.
+        part: Option<usize>,
+    },
+    /// Re-runs a day's part whenever its input (or the manifest) changes, and checks the answer
+    /// against the manifest.  Runs until interrupted.
+    Watch {
+        /// The AoC day (1-25).
+        day: usize,
+        /// The AoC day's question part (1-2).  Both parts are answered when omitted.
+        part: Option<usize>,
+        /// Optional path to input file.  If not provided, the day's input is found in the input
+        /// directory.
+        #[structopt(parse(from_os_str))]
+        input_data_file: Option<PathBuf>,
+        /// Path to the answers manifest, which is also watched.  Skipped when it doesn't exist.
+        #[structopt(long, parse(from_os_str), default_value = "answers.toml")]
+        manifest: PathBuf,
+        /// How often to check for changes, in milliseconds.
+        #[structopt(long, default_value = "500")]
+        interval: u64,
+    },
+    /// Loads a day's input once, then answers questions about it, one command per line (eg: how
+    /// many bags a day 7 bag contains).  Supports days 4 and 7; `help` lists the commands.
+    Repl {
+        /// The AoC day (4 or 7).
+        day: usize,
+        /// Optional path to input file.  If not provided, the day's input is found in the input
+        /// directory.
+        #[structopt(parse(from_os_str))]
+        input_data_file: Option<PathBuf>,
+    },
+    /// Checks solutions against the known answers in a manifest, and reports any mismatches.
+    Verify {
+        /// Path to the answers manifest.
+        #[structopt(parse(from_os_str), default_value = "answers.toml")]
+        manifest: PathBuf,
+    },
 }
@@ -0,0 +1,305 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::fs;
+use std::path::Path;
+use std::time::Duration;
+
+use crate::inputs::InputSource;
+use crate::solution::Solver;
+use crate::timing::Timing;
+use crate::{Error, Result};
+
+/// Summary statistics over a set of duration samples.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct Stats {
+    pub min: Duration,
+    pub median: Duration,
+    pub mean: Duration,
+    pub stddev: Duration,
+}
+
+impl Stats {
+    /// Returns `None` when there are no samples to summarize.
+    pub fn from_samples(samples: &[Duration]) -> Option<Self> {
+        let mut sorted = samples.to_vec();
+        sorted.sort();
+        let min = *sorted.first()?;
+        let median = match sorted.len() % 2 {
+            0 => (sorted[sorted.len() / 2 - 1] + sorted[sorted.len() / 2]) / 2,
+            _ => sorted[sorted.len() / 2],
+        };
+        let mean = sorted.iter().sum::<Duration>() / sorted.len() as u32;
+        let variance = sorted
+            .iter()
+            .map(|sample| (sample.as_secs_f64() - mean.as_secs_f64()).powi(2))
+            .sum::<f64>()
+            / sorted.len() as f64;
+        Some(Self {
+            min,
+            median,
+            mean,
+            stddev: Duration::from_secs_f64(variance.sqrt()),
+        })
+    }
+}
+
+/// The statistics from running a single day's part repeatedly.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct Benchmark {
+    pub year: usize,
+    pub day: usize,
+    pub part: usize,
+    pub iterations: usize,
+    pub parse: Stats,
+    pub solve: Stats,
+    pub total: Stats,
+}
+
+/// How many times a day's part is run, unless configured otherwise.
+pub const DEFAULT_ITERATIONS: usize = 10;
+
+/// Runs the day's part `iterations` times, reading the input file on every iteration so that the
+/// read is included in the parse phase.  Stdin can only be read once, so it can't be benchmarked.
+pub fn bench(
+    solver: &dyn Solver,
+    part: usize,
+    source: &InputSource,
+    iterations: usize,
+) -> Result<Benchmark> {
+    if *source == InputSource::Stdin {
+        return Err(Error::InvalidInput(
+            "benchmarks need an input file, since stdin can only be read once".into(),
+        ));
+    }
+    let timings = (0..iterations)
+        .map(|_| Ok(solver.run_timed(part, source.open()?)?.1))
+        .collect::<Result<Vec<Timing>>>()?;
+
+    let stats = |phase: fn(&Timing) -> Duration| {
+        Stats::from_samples(&timings.iter().map(phase).collect::<Vec<Duration>>())
+            .ok_or_else(|| Error::InvalidInput("benchmarks need at least one iteration".into()))
+    };
+    Ok(Benchmark {
+        year: solver.metadata().year,
+        day: solver.metadata().day,
+        part,
+        iterations,
+        parse: stats(|timing| timing.parse)?,
+        solve: stats(|timing| timing.solve)?,
+        total: stats(Timing::total)?,
+    })
+}
+
+impl Benchmark {
+    fn key(&self) -> (usize, usize, usize) {
+        (self.year, self.day, self.part)
+    }
+}
+
+impl fmt::Display for Benchmark {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "day {}, part {}, {} iterations:",
+            self.day, self.part, self.iterations
+        )?;
+        writeln!(
+            f,
+            "phase | {:>12} | {:>12} | {:>12} | {:>12}",
+            "min", "median", "mean", "stddev"
+        )?;
+        for (phase, stats) in &[
+            ("parse", self.parse),
+            ("solve", self.solve),
+            ("total", self.total),
+        ] {
+            writeln!(
+                f,
+                "{:<5} | {:>12} | {:>12} | {:>12} | {:>12}",
+                phase,
+                format!("{:?}", stats.min),
+                format!("{:?}", stats.median),
+                format!("{:?}", stats.mean),
+                format!("{:?}", stats.stddev),
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Saved benchmarks, to compare later runs against.  There's at most one per year's day's part.
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[serde(transparent)]
+pub struct Baseline(Vec<Benchmark>);
+
+impl Baseline {
+    /// A missing file has no benchmarks yet.
+    pub fn load(path: &Path) -> Result<Self> {
+        let load = || Ok(serde_json::from_str(&fs::read_to_string(path)?)?);
+        match path.exists() {
+            true => load().map_err(|err: Error| err.in_file(path)),
+            false => Ok(Self::default()),
+        }
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        let save = || Ok(fs::write(path, serde_json::to_string_pretty(self)? + "\n")?);
+        save().map_err(|err: Error| err.in_file(path))
+    }
+
+    pub fn get(&self, year: usize, day: usize, part: usize) -> Option<&Benchmark> {
+        self.0
+            .iter()
+            .find(|benchmark| benchmark.key() == (year, day, part))
+    }
+
+    /// Records the benchmark, replacing any earlier one for the same day's part.
+    pub fn record(&mut self, benchmark: Benchmark) {
+        self.0.retain(|saved| saved.key() != benchmark.key());
+        self.0.push(benchmark);
+        self.0.sort_by_key(Benchmark::key);
+    }
+
+    /// Compares the benchmark against the saved one for the same day's part, if there is one.  A
+    /// median total time that's more than `threshold` percent slower is a regression.
+    pub fn compare(&self, benchmark: &Benchmark, threshold: f64) -> Option<Comparison> {
+        let saved = self.get(benchmark.year, benchmark.day, benchmark.part)?;
+        Some(Comparison {
+            day: benchmark.day,
+            part: benchmark.part,
+            baseline: saved.total.median,
+            current: benchmark.total.median,
+            threshold,
+        })
+    }
+}
+
+/// How a benchmark's median total time compares to its baseline's.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Comparison {
+    pub day: usize,
+    pub part: usize,
+    pub baseline: Duration,
+    pub current: Duration,
+    /// The percentage slowdown that counts as a regression.
+    pub threshold: f64,
+}
+
+impl Comparison {
+    /// The percentage change from the baseline: positive when slower, negative when faster.
+    pub fn change(&self) -> f64 {
+        match self.baseline.as_secs_f64() {
+            baseline if baseline > 0.0 => {
+                (self.current.as_secs_f64() - baseline) / baseline * 100.0
+            }
+            _ => 0.0,
+        }
+    }
+
+    pub fn is_regression(&self) -> bool {
+        self.change() > self.threshold
+    }
+}
+
+impl fmt::Display for Comparison {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "day {}, part {}: median {:?} -> {:?} ({:+.1}%)",
+            self.day,
+            self.part,
+            self.baseline,
+            self.current,
+            self.change()
+        )?;
+        if self.is_regression() {
+            write!(f, ", a regression beyond the {}% threshold", self.threshold)?;
+        }
+        Ok(())
+    }
+}
+
+#[test]
+fn test_stats_from_samples() {
+    let samples = [4, 1, 3, 2]
+        .iter()
+        .map(|&millis| Duration::from_millis(millis))
+        .collect::<Vec<Duration>>();
+    let stats = Stats::from_samples(&samples).unwrap();
+    assert_eq!(stats.min, Duration::from_millis(1));
+    assert_eq!(stats.median, Duration::from_micros(2500));
+    assert_eq!(stats.mean, Duration::from_micros(2500));
+    assert_eq!(stats.stddev.as_micros(), 1118);
+
+    assert_eq!(Stats::from_samples(&[]), None);
+}
+
+#[test]
+fn test_bench() -> Result<()> {
+    let solver = crate::exercises::find(2020, 1).unwrap();
+    let source = InputSource::File("inputs/day_01.txt".into());
+    let benchmark = bench(solver, 2, &source, 3)?;
+    assert_eq!((benchmark.year, benchmark.day), (2020, 1));
+    assert_eq!(benchmark.iterations, 3);
+    assert!(benchmark.total.min <= benchmark.total.median);
+    assert!(bench(solver, 2, &source, 0).is_err());
+    assert!(bench(solver, 2, &InputSource::Stdin, 3).is_err());
+    Ok(())
+}
+
+#[cfg(test)]
+fn benchmark(day: usize, part: usize, median_micros: u64) -> Benchmark {
+    let stats = Stats::from_samples(&[Duration::from_micros(median_micros)]).unwrap();
+    Benchmark {
+        year: 2020,
+        day,
+        part,
+        iterations: 1,
+        parse: stats,
+        solve: stats,
+        total: stats,
+    }
+}
+
+#[test]
+fn test_baseline() -> Result<()> {
+    let mut baseline = Baseline::default();
+    assert_eq!(baseline.compare(&benchmark(7, 2, 100), 10.0), None);
+
+    baseline.record(benchmark(7, 2, 200));
+    baseline.record(benchmark(1, 1, 50));
+    baseline.record(benchmark(7, 2, 100));
+    assert_eq!(baseline.0.len(), 2);
+    assert_eq!(baseline.0[0].day, 1);
+    let other_year = Benchmark {
+        year: 2019,
+        ..benchmark(7, 2, 10)
+    };
+    assert_eq!(baseline.compare(&other_year, 10.0), None);
+
+    let slower = baseline.compare(&benchmark(7, 2, 125), 10.0).unwrap();
+    assert!((slower.change() - 25.0).abs() < 1e-9);
+    assert!(slower.is_regression());
+    assert_eq!(
+        slower.to_string(),
+        "day 7, part 2: median 100µs -> 125µs (+25.0%), a regression beyond the 10% threshold"
+    );
+    let within = baseline.compare(&benchmark(7, 2, 109), 10.0).unwrap();
+    assert!(!within.is_regression());
+    let faster = baseline.compare(&benchmark(7, 2, 50), 10.0).unwrap();
+    assert!(!faster.is_regression());
+    assert_eq!(
+        faster.to_string(),
+        "day 7, part 2: median 100µs -> 50µs (-50.0%)"
+    );
+
+    let path = std::env::temp_dir().join(format!("aoc_baseline_{}.json", std::process::id()));
+    assert_eq!(Baseline::load(&path)?.0.len(), 0);
+    baseline.save(&path)?;
+    assert_eq!(
+        Baseline::load(&path)?.get(2020, 7, 2),
+        baseline.get(2020, 7, 2)
+    );
+    fs::remove_file(&path)?;
+    Ok(())
+}
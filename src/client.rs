@@ -0,0 +1,301 @@
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use crate::inputs::InputLocator;
+use crate::{Error, Result};
+
+/// The puzzles' website.
+pub const DEFAULT_BASE_URL: &str = "https://adventofcode.com";
+
+/// Identifies us to the website, as its maintainer asks of automated tools.
+const USER_AGENT: &str = concat!(
+    "github.com/modulitos/aoc2020 by modulitos@users.noreply.github.com (",
+    env!("CARGO_PKG_NAME"),
+    " ",
+    env!("CARGO_PKG_VERSION"),
+    ")"
+);
+
+/// The value of the website's `session` cookie, which identifies whose puzzle inputs to download.
+/// It's a secret, so it's never printed.
+#[derive(Clone, Eq, PartialEq)]
+pub struct Session(String);
+
+impl Session {
+    /// The environment variable holding the token, which takes precedence over the session file.
+    pub const ENV_VAR: &'static str = "AOC_SESSION";
+    pub const DEFAULT_FILE: &'static str = ".aoc_session";
+
+    pub fn new(token: impl Into<String>) -> Self {
+        Self(token.into())
+    }
+
+    /// Reads the token from `AOC_SESSION`, or else from the session file.  Returns `None` when
+    /// neither holds a token.
+    pub fn load(file: &Path) -> Result<Option<Self>> {
+        Self::resolve(std::env::var(Self::ENV_VAR).ok(), file)
+    }
+
+    fn resolve(env: Option<String>, file: &Path) -> Result<Option<Self>> {
+        let token = match env {
+            Some(token) => token,
+            None if file.is_file() => fs::read_to_string(file)?,
+            None => return Ok(None),
+        };
+        let token = token.trim();
+        Ok(match token.is_empty() {
+            true => None,
+            false => Some(Self::new(token)),
+        })
+    }
+}
+
+impl fmt::Debug for Session {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Session(<redacted>)")
+    }
+}
+
+/// A client for the puzzles' website.  The session is only needed once a request is made.
+#[derive(Debug)]
+pub struct Client {
+    base_url: String,
+    session: Option<Session>,
+    agent: ureq::Agent,
+}
+
+impl Client {
+    pub fn new(base_url: impl Into<String>, session: Option<Session>) -> Self {
+        Self {
+            base_url: base_url.into().trim_end_matches('/').to_string(),
+            session,
+            agent: ureq::AgentBuilder::new()
+                .user_agent(USER_AGENT)
+                .timeout(Duration::from_secs(30))
+                .build(),
+        }
+    }
+
+    pub fn base_url(&self) -> &str {
+        &self.base_url
+    }
+
+    /// Downloads the year's day's puzzle input.
+    pub fn input(&self, year: usize, day: usize) -> Result<String> {
+        let url = format!("{}/{}/day/{}/input", self.base_url, year, day);
+        Ok(self
+            .agent
+            .get(&url)
+            .set("Cookie", &self.cookie()?)
+            .call()?
+            .into_string()?)
+    }
+
+    /// Posts an answer for the year's day's part, and returns the response page.
+    pub fn answer(&self, year: usize, day: usize, part: usize, answer: &str) -> Result<String> {
+        let url = format!("{}/{}/day/{}/answer", self.base_url, year, day);
+        Ok(self
+            .agent
+            .post(&url)
+            .set("Cookie", &self.cookie()?)
+            .send_form(&[("level", &part.to_string()), ("answer", answer)])?
+            .into_string()?)
+    }
+
+    fn cookie(&self) -> Result<String> {
+        let session = self.session.as_ref().ok_or_else(|| {
+            Error::InvalidInput(format!(
+                "a session token is required: set {} or write it to {}",
+                Session::ENV_VAR,
+                Session::DEFAULT_FILE
+            ))
+        })?;
+        Ok(format!("session={}", session.0))
+    }
+}
+
+/// Whether `fetch` downloaded an input, or found it already cached.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Fetched {
+    Cached(PathBuf),
+    Downloaded(PathBuf),
+}
+
+/// Downloads the day's puzzle input, for the inputs' year, to its conventional location, unless
+/// it's already there.  The inputs never change, so a cached input is never downloaded again.  An
+/// empty file (eg: from `scaffold`) doesn't count as cached.
+pub fn fetch(client: &Client, inputs: &InputLocator, day: usize) -> Result<Fetched> {
+    if !(1..=25).contains(&day) {
+        return Err(Error::InvalidDayOrPartArg(day, 1));
+    }
+    let path = inputs.puzzle_input(day);
+    if fs::metadata(&path).is_ok_and(|metadata| metadata.len() > 0) {
+        return Ok(Fetched::Cached(path));
+    }
+    let input = client.input(inputs.year(), day)?;
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    // Writing to a temporary file first, so that an interrupted write isn't mistaken for a cached
+    // input later:
+    let partial = path.with_extension("txt.partial");
+    fs::write(&partial, input)?;
+    fs::rename(&partial, &path)?;
+    Ok(Fetched::Downloaded(path))
+}
+
+/// A request received by a `MockServer`.
+#[cfg(test)]
+#[derive(Debug)]
+pub(crate) struct Received {
+    pub method: String,
+    pub url: String,
+    pub headers: Vec<(String, String)>,
+    pub body: String,
+}
+
+#[cfg(test)]
+impl Received {
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(field, _)| field.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+/// A local HTTP server for tests, which answers each request with the next of the given responses,
+/// then shuts down.
+#[cfg(test)]
+pub(crate) struct MockServer {
+    pub url: String,
+    handle: std::thread::JoinHandle<Vec<Received>>,
+}
+
+#[cfg(test)]
+impl MockServer {
+    pub fn start(responses: Vec<(u16, &str)>) -> Self {
+        let server = tiny_http::Server::http("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", server.server_addr());
+        let responses = responses
+            .into_iter()
+            .map(|(status, body)| (status, body.to_string()))
+            .collect::<Vec<(u16, String)>>();
+        let handle = std::thread::spawn(move || {
+            responses
+                .into_iter()
+                .map(|(status, body)| {
+                    let mut request = server.recv().unwrap();
+                    let mut received = Received {
+                        method: request.method().to_string(),
+                        url: request.url().to_string(),
+                        headers: request
+                            .headers()
+                            .iter()
+                            .map(|header| (header.field.to_string(), header.value.to_string()))
+                            .collect(),
+                        body: String::new(),
+                    };
+                    request
+                        .as_reader()
+                        .read_to_string(&mut received.body)
+                        .unwrap();
+                    request
+                        .respond(tiny_http::Response::from_string(body).with_status_code(status))
+                        .unwrap();
+                    received
+                })
+                .collect()
+        });
+        Self { url, handle }
+    }
+
+    /// Waits for every response to be sent, and returns the requests that were received.
+    pub fn received(self) -> Vec<Received> {
+        self.handle.join().unwrap()
+    }
+}
+
+#[test]
+fn test_session() -> Result<()> {
+    let dir = std::env::temp_dir().join(format!("aoc_session_{}", std::process::id()));
+    fs::create_dir_all(&dir)?;
+    let file = dir.join(Session::DEFAULT_FILE);
+    assert_eq!(Session::resolve(None, &file)?, None);
+
+    fs::write(&file, "from-file\n")?;
+    assert_eq!(
+        Session::resolve(None, &file)?,
+        Some(Session::new("from-file"))
+    );
+    assert_eq!(
+        Session::resolve(Some("from-env".into()), &file)?,
+        Some(Session::new("from-env"))
+    );
+    assert_eq!(
+        format!("{:?}", Session::new("secret")),
+        "Session(<redacted>)"
+    );
+
+    fs::remove_dir_all(&dir)?;
+    Ok(())
+}
+
+#[test]
+fn test_fetch() -> Result<()> {
+    let dir = std::env::temp_dir().join(format!("aoc_fetch_{}", std::process::id()));
+    let inputs = InputLocator::new(&dir);
+    // The server answers a single request, so a second download would fail:
+    let server = MockServer::start(vec![(200, "1721\n979\n")]);
+    let client = Client::new(&server.url, Some(Session::new("abc123")));
+
+    let path = inputs.puzzle_input(1);
+    assert_eq!(
+        fetch(&client, &inputs, 1)?,
+        Fetched::Downloaded(path.clone())
+    );
+    assert_eq!(fs::read_to_string(&path)?, "1721\n979\n");
+    assert_eq!(fetch(&client, &inputs, 1)?, Fetched::Cached(path));
+
+    let received = server.received();
+    assert_eq!(received.len(), 1);
+    assert_eq!(received[0].method, "GET");
+    assert_eq!(received[0].url, "/2020/day/1/input");
+    assert_eq!(received[0].header("Cookie"), Some("session=abc123"));
+    assert_eq!(received[0].header("User-Agent"), Some(USER_AGENT));
+
+    // Another year's input is fetched from that year, into its own directory:
+    let server = MockServer::start(vec![(200, "+1\n-2\n")]);
+    let client = Client::new(&server.url, Some(Session::new("abc123")));
+    let inputs = inputs.with_year(2018);
+    assert_eq!(
+        fetch(&client, &inputs, 1)?,
+        Fetched::Downloaded(dir.join("2018/day_01.txt"))
+    );
+    assert_eq!(server.received()[0].url, "/2018/day/1/input");
+
+    fs::remove_dir_all(&dir)?;
+    Ok(())
+}
+
+#[test]
+fn test_fetch_errors() -> Result<()> {
+    let dir = std::env::temp_dir().join(format!("aoc_fetch_errors_{}", std::process::id()));
+    let inputs = InputLocator::new(&dir);
+    let server = MockServer::start(vec![(400, "Puzzle inputs differ by user.")]);
+
+    // Nothing is requested without a session:
+    assert!(fetch(&Client::new(&server.url, None), &inputs, 2).is_err());
+    assert!(fetch(&Client::new(&server.url, None), &inputs, 26).is_err());
+
+    let client = Client::new(&server.url, Some(Session::new("expired")));
+    let err = fetch(&client, &inputs, 2).unwrap_err();
+    assert!(err.to_string().contains("400"), "{}", err);
+    assert!(!inputs.puzzle_input(2).exists());
+    assert_eq!(server.received().len(), 1);
+
+    let _ = fs::remove_dir_all(&dir);
+    Ok(())
+}
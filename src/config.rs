@@ -0,0 +1,383 @@
+use serde::Deserialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::args::{Args, Command};
+use crate::exercises::DEFAULT_YEAR;
+use crate::format::Format;
+use crate::{Error, Result};
+
+/// Defaults for the command line, loaded from a TOML file.  A flag that's given on the command line
+/// always wins over the configuration.  A leading `~/` in a path is expanded to the home directory,
+/// eg:
+///
+/// ```toml
+/// input_dir = "inputs"
+/// format = "json"
+/// session_file = "~/.aoc_session"
+/// base_url = "http://localhost:8080"
+/// year = 2020
+/// day = 7
+///
+/// [bench]
+/// iterations = 100
+///
+/// [[days]]
+/// day = 7
+/// part = 2
+/// input = "inputs/day_07.custom.txt"
+/// ```
+#[derive(Clone, Debug, Default, Deserialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct Config {
+    pub input_dir: Option<PathBuf>,
+    pub format: Option<Format>,
+    pub session_file: Option<PathBuf>,
+    pub base_url: Option<String>,
+    pub history: Option<PathBuf>,
+    /// The year to run when none is given.
+    pub year: Option<usize>,
+    /// The day to run when none is given.
+    pub day: Option<usize>,
+    #[serde(default)]
+    pub bench: BenchConfig,
+    #[serde(default)]
+    pub days: Vec<DayConfig>,
+}
+
+#[derive(Clone, Debug, Default, Deserialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct BenchConfig {
+    pub iterations: Option<usize>,
+}
+
+/// Defaults for running a single day.
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct DayConfig {
+    /// Defaults to the configured year, or else 2020.
+    pub year: Option<usize>,
+    pub day: usize,
+    /// The part to answer when none is given, rather than both.
+    pub part: Option<usize>,
+    /// The input to use when no path is given, rather than the input directory's.
+    pub input: Option<PathBuf>,
+    /// Whether to use the day's example input.
+    #[serde(default)]
+    pub example: bool,
+}
+
+impl Config {
+    /// The per-repo configuration file, in the working directory.
+    pub const DEFAULT_FILE: &'static str = "aoc.toml";
+
+    pub fn from_path(path: &Path) -> Result<Self> {
+        let load = || Ok(toml::from_str::<Self>(&fs::read_to_string(path)?)?);
+        let config = load().map_err(|err: Error| err.in_file(path))?;
+        let home = std::env::var_os("HOME").map(PathBuf::from);
+        Ok(config.expand_home(home.as_deref()))
+    }
+
+    /// Expands a leading `~/` in each path to the home directory, as a shell would.  Without a
+    /// home directory, the paths are left as they are.
+    fn expand_home(mut self, home: Option<&Path>) -> Self {
+        let expand = |path: Option<PathBuf>| path.map(|path| expand_home(path, home));
+        self.input_dir = expand(self.input_dir);
+        self.session_file = expand(self.session_file);
+        self.history = expand(self.history);
+        for day in &mut self.days {
+            day.input = expand(day.input.take());
+        }
+        self
+    }
+
+    /// Loads the per-repo configuration (`path`, or else `aoc.toml`), falling back to the user's
+    /// `aoc/config.toml` in `$XDG_CONFIG_HOME` (or `~/.config`) for anything it doesn't set.  An
+    /// explicitly given path must exist, while missing default files are skipped.
+    pub fn load(path: Option<&Path>) -> Result<Self> {
+        let repo = match path {
+            Some(path) => Self::from_path(path)?,
+            None => Self::from_optional_path(Path::new(Self::DEFAULT_FILE))?,
+        };
+        let user = match Self::user_file() {
+            Some(path) => Self::from_optional_path(&path)?,
+            None => Self::default(),
+        };
+        Ok(repo.or(user))
+    }
+
+    fn from_optional_path(path: &Path) -> Result<Self> {
+        match path.is_file() {
+            true => Self::from_path(path),
+            false => Ok(Self::default()),
+        }
+    }
+
+    fn user_file() -> Option<PathBuf> {
+        let dir = match std::env::var_os("XDG_CONFIG_HOME") {
+            Some(dir) if !dir.is_empty() => PathBuf::from(dir),
+            _ => PathBuf::from(std::env::var_os("HOME")?).join(".config"),
+        };
+        Some(dir.join("aoc").join("config.toml"))
+    }
+
+    /// Each setting, or else the fallback's.  A day's settings are taken as a whole from whichever
+    /// configuration has them, and days without a year are for the combined configuration's year.
+    pub fn or(self, fallback: Self) -> Self {
+        let fallback_days = fallback
+            .days
+            .into_iter()
+            .filter(|fallback| self.days.iter().all(|day| day.key() != fallback.key()))
+            .collect::<Vec<DayConfig>>();
+        let mut days = self.days;
+        days.extend(fallback_days);
+        Self {
+            input_dir: self.input_dir.or(fallback.input_dir),
+            format: self.format.or(fallback.format),
+            session_file: self.session_file.or(fallback.session_file),
+            base_url: self.base_url.or(fallback.base_url),
+            history: self.history.or(fallback.history),
+            year: self.year.or(fallback.year),
+            day: self.day.or(fallback.day),
+            bench: BenchConfig {
+                iterations: self.bench.iterations.or(fallback.bench.iterations),
+            },
+            days,
+        }
+    }
+
+    /// The settings for the year's day.  Days without a year are for the configured year.
+    pub fn day(&self, year: usize, day: usize) -> Option<&DayConfig> {
+        let configured = self.year.unwrap_or(DEFAULT_YEAR);
+        self.days
+            .iter()
+            .find(|config| (config.year.unwrap_or(configured), config.day) == (year, day))
+    }
+
+    /// Fills in whatever the command line left out.
+    pub fn apply(&self, mut args: Args) -> Args {
+        args.input_dir = args.input_dir.or_else(|| self.input_dir.clone());
+        args.format = args.format.or(self.format);
+        args.session_file = args.session_file.or_else(|| self.session_file.clone());
+        args.base_url = args.base_url.or_else(|| self.base_url.clone());
+        args.history = args.history.or_else(|| self.history.clone());
+        args.year = args.year.or(self.year);
+        let year = args.year.unwrap_or(DEFAULT_YEAR);
+        match &mut args.command {
+            None => {
+                args.day = args.day.or(self.day);
+                if let Some(config) = args.day.and_then(|day| self.day(year, day)) {
+                    args.part = args.part.or(config.part);
+                    args.input_data_file = args.input_data_file.or_else(|| config.input.clone());
+                    args.example |= config.example;
+                }
+            }
+            Some(Command::Bench { iterations, .. }) => {
+                *iterations = iterations.or(self.bench.iterations);
+            }
+            Some(_) => {}
+        }
+        args
+    }
+}
+
+impl DayConfig {
+    fn key(&self) -> (Option<usize>, usize) {
+        (self.year, self.day)
+    }
+}
+
+fn expand_home(path: PathBuf, home: Option<&Path>) -> PathBuf {
+    match (home, path.strip_prefix("~")) {
+        (Some(home), Ok(rest)) => home.join(rest),
+        _ => path,
+    }
+}
+
+#[cfg(test)]
+fn parse_args(args: &[&str]) -> Args {
+    use structopt::StructOpt;
+    Args::from_iter(std::iter::once("aoc").chain(args.iter().copied()))
+}
+
+#[test]
+fn test_config_from_toml() -> Result<()> {
+    let config: Config = toml::from_str(
+        r#"
+        input_dir = "puzzles"
+        format = "json"
+        day = 7
+
+        [bench]
+        iterations = 100
+
+        [[days]]
+        day = 7
+        part = 2
+        input = "custom.txt"
+        "#,
+    )?;
+    assert_eq!(config.input_dir, Some(PathBuf::from("puzzles")));
+    assert_eq!(config.format, Some(Format::Json));
+    assert_eq!(config.bench.iterations, Some(100));
+    assert_eq!(config.day(2020, 7).and_then(|day| day.part), Some(2));
+    assert_eq!(config.day(2020, 1), None);
+    assert_eq!(config.day(2019, 7), None);
+
+    assert!(toml::from_str::<Config>("input_directory = \"typo\"").is_err());
+    assert!(toml::from_str::<Config>("format = \"yaml\"").is_err());
+    Ok(())
+}
+
+#[test]
+fn test_config_expand_home() -> Result<()> {
+    let config: Config = toml::from_str(
+        r#"
+        session_file = "~/.aoc_session"
+        history = "~user/history.json"
+        input_dir = "inputs/~"
+
+        [[days]]
+        day = 7
+        input = "~/puzzles/day_07.txt"
+        "#,
+    )?;
+    let config = config.expand_home(Some(Path::new("/home/me")));
+    assert_eq!(
+        config.session_file,
+        Some(PathBuf::from("/home/me/.aoc_session"))
+    );
+    assert_eq!(config.history, Some(PathBuf::from("~user/history.json")));
+    assert_eq!(config.input_dir, Some(PathBuf::from("inputs/~")));
+    assert_eq!(
+        config.days[0].input,
+        Some(PathBuf::from("/home/me/puzzles/day_07.txt"))
+    );
+    assert_eq!(
+        expand_home("~/.aoc_session".into(), None),
+        PathBuf::from("~/.aoc_session")
+    );
+    Ok(())
+}
+
+#[test]
+fn test_config_or() {
+    let day = |day, part| DayConfig {
+        year: None,
+        day,
+        part: Some(part),
+        input: None,
+        example: false,
+    };
+    let repo = Config {
+        format: Some(Format::Csv),
+        days: vec![day(7, 2)],
+        ..Config::default()
+    };
+    let other_year = DayConfig {
+        year: Some(2019),
+        ..day(7, 1)
+    };
+    let user = Config {
+        format: Some(Format::Json),
+        session_file: Some("user_session".into()),
+        year: Some(2019),
+        days: vec![day(7, 1), day(1, 1), other_year.clone()],
+        ..Config::default()
+    };
+    let config = repo.or(user);
+    assert_eq!(config.format, Some(Format::Csv));
+    assert_eq!(config.session_file, Some(PathBuf::from("user_session")));
+    assert_eq!(config.year, Some(2019));
+    assert_eq!(config.days, vec![day(7, 2), day(1, 1), other_year]);
+}
+
+#[test]
+fn test_config_apply() -> Result<()> {
+    let config: Config = toml::from_str(
+        r#"
+        format = "json"
+        base_url = "http://localhost:8080"
+        day = 7
+
+        [bench]
+        iterations = 100
+
+        [[days]]
+        day = 7
+        part = 2
+        input = "custom.txt"
+        "#,
+    )?;
+
+    let args = config.apply(parse_args(&[]));
+    assert_eq!((args.day, args.part), (Some(7), Some(2)));
+    assert_eq!(args.base_url, Some("http://localhost:8080".into()));
+    assert_eq!(args.input_data_file, Some(PathBuf::from("custom.txt")));
+    assert_eq!(args.format, Some(Format::Json));
+
+    // Flags win over the configuration:
+    let args = config.apply(parse_args(&["7", "1", "other.txt", "--format", "csv"]));
+    assert_eq!((args.day, args.part), (Some(7), Some(1)));
+    assert_eq!(args.input_data_file, Some(PathBuf::from("other.txt")));
+    assert_eq!(args.format, Some(Format::Csv));
+
+    // A day without settings answers both parts from its usual input:
+    let args = config.apply(parse_args(&["1"]));
+    assert_eq!(
+        (args.day, args.part, args.input_data_file),
+        (Some(1), None, None)
+    );
+
+    // Days are configured per year:
+    let args = config.apply(parse_args(&["7", "--year", "2019"]));
+    assert_eq!(
+        (args.year, args.part, args.input_data_file),
+        (Some(2019), None, None)
+    );
+    let other_year: Config = toml::from_str(
+        r#"
+        year = 2019
+
+        [[days]]
+        day = 7
+        part = 2
+
+        [[days]]
+        year = 2020
+        day = 7
+        part = 1
+        "#,
+    )?;
+    let args = other_year.apply(parse_args(&["7"]));
+    assert_eq!((args.year, args.part), (Some(2019), Some(2)));
+    let args = other_year.apply(parse_args(&["7", "--year", "2020"]));
+    assert_eq!((args.year, args.part), (Some(2020), Some(1)));
+
+    match config.apply(parse_args(&["bench", "7", "2"])).command {
+        Some(Command::Bench { iterations, .. }) => assert_eq!(iterations, Some(100)),
+        command => panic!("expected a bench command, found: {:?}", command),
+    }
+    match config
+        .apply(parse_args(&["bench", "7", "2", "-n", "5"]))
+        .command
+    {
+        Some(Command::Bench { iterations, .. }) => assert_eq!(iterations, Some(5)),
+        command => panic!("expected a bench command, found: {:?}", command),
+    }
+    Ok(())
+}
+
+#[test]
+fn test_config_from_path_error() -> Result<()> {
+    let path = std::env::temp_dir().join(format!("aoc_config_{}.toml", std::process::id()));
+    fs::write(&path, "year = ")?;
+    let err = Config::from_path(&path).unwrap_err();
+    assert!(err
+        .chain()
+        .to_string()
+        .starts_with(&format!("Error in `{}`: TOML Error", path.display())));
+
+    fs::remove_file(&path)?;
+    Ok(())
+}
@@ -1,17 +1,25 @@
 use std::char::ParseCharError as StdParseCharError;
 use std::convert::Infallible as StdInfallible;
+use std::error::Error as StdError;
+use std::fmt;
 use std::io::Error as StdIoError;
 use std::num::ParseIntError as StdParseIntError;
+use std::path::{Path, PathBuf};
 
 use thiserror::Error;
 
+pub use exit_code::ExitCode;
 use io_error::IoError;
 use parse_int_error::ParseIntError;
+pub use span_error::SpanError;
 
+mod exit_code;
 mod io_error;
 mod parse_int_error;
+mod span_error;
 
-#[allow(clippy::pub_enum_variant_names)]
+/// Each variant's message only describes its own layer, and any underlying error is its `source`,
+/// so that `Error::chain` can report every cause exactly once.
 #[derive(Debug, Error)]
 pub enum Error {
     #[error("IO Error")]
@@ -23,6 +31,18 @@ pub enum Error {
     #[error("Parse Char Error")]
     ParseCharError(#[from] StdParseCharError),
 
+    #[error("JSON Error")]
+    JsonError(#[from] serde_json::Error),
+
+    #[error("TOML Error")]
+    TomlError(#[from] toml::de::Error),
+
+    #[error("Thread Pool Error")]
+    ThreadPoolError(#[from] rayon::ThreadPoolBuildError),
+
+    #[error("HTTP Error: `{0}`")]
+    HttpError(String),
+
     #[error("Infallible Error")]
     Infallible(#[from] StdInfallible),
 
@@ -32,8 +52,119 @@ pub enum Error {
     #[error("Invalid State Error: `{0}`")]
     InvalidState(String),
 
+    #[error(transparent)]
+    SpanError(#[from] SpanError),
+
+    #[error("Error on line {line}")]
+    LineError {
+        line: usize,
+        #[source]
+        source: Box<Error>,
+    },
+
+    #[error("Error in `{}`", path.display())]
+    FileError {
+        path: PathBuf,
+        #[source]
+        source: Box<Error>,
+    },
+
     #[error("Invalid Day or Part: day: `{0}`, part: `{1}`")]
     InvalidDayOrPartArg(usize, usize),
+
+    #[error("Invalid Year: `{0}`, the first AoC was in 2015")]
+    InvalidYearArg(usize),
+
+    #[error("Day `{1}` of {0} has not been implemented yet")]
+    UnimplementedDay(usize, usize),
+
+    #[error("Day `{1}`, part `{2}` of {0} has not been implemented yet")]
+    UnimplementedPart(usize, usize, usize),
+}
+
+impl Error {
+    /// Attaches the 1-indexed line number of the input that caused this error.  An error which
+    /// already has a line number keeps it, and a span relative to that line is moved onto it.
+    pub fn at_line(self, line: usize) -> Self {
+        match self {
+            Self::SpanError(span) => Self::SpanError(SpanError {
+                line: span.line + line - 1,
+                ..span
+            }),
+            Self::LineError { .. } => self,
+            source => Self::LineError {
+                line,
+                source: Box::new(source),
+            },
+        }
+    }
+
+    /// Attaches the path of the file that caused this error, eg: one that couldn't be read or
+    /// deserialized.
+    pub fn in_file(self, path: &Path) -> Self {
+        Self::FileError {
+            path: path.to_path_buf(),
+            source: Box::new(self),
+        }
+    }
+
+    /// Displays the error along with its causes: on one line, separated by colons, or with `{:#}`
+    /// on a line each.
+    pub fn chain(&self) -> Chain<'_> {
+        Chain(self)
+    }
+
+    /// The process exit code for this kind of error.
+    pub fn exit_code(&self) -> ExitCode {
+        match self {
+            Self::InvalidDayOrPartArg(..) | Self::InvalidYearArg(_) => ExitCode::Usage,
+            Self::IoError(_) | Self::HttpError(_) => ExitCode::Io,
+            Self::ParseIntError(_)
+            | Self::ParseCharError(_)
+            | Self::JsonError(_)
+            | Self::TomlError(_)
+            | Self::InvalidInput(_)
+            | Self::SpanError(_) => ExitCode::BadInput,
+            Self::InvalidState(_) | Self::ThreadPoolError(_) | Self::Infallible(_) => {
+                ExitCode::InternalState
+            }
+            Self::UnimplementedDay(..) | Self::UnimplementedPart(..) => ExitCode::Unsolved,
+            Self::LineError { source, .. } | Self::FileError { source, .. } => source.exit_code(),
+        }
+    }
+
+    /// The span of input that caused this error, if it is known.
+    pub fn span(&self) -> Option<&SpanError> {
+        match self {
+            Self::SpanError(span) => Some(span),
+            Self::LineError { source, .. } => source.span(),
+            _ => None,
+        }
+    }
+}
+
+/// An error, followed by each of its causes.
+#[derive(Clone, Copy, Debug)]
+pub struct Chain<'a>(&'a Error);
+
+impl<'a> Chain<'a> {
+    /// The error's causes, outermost first, excluding the error itself.
+    pub fn causes(&self) -> impl Iterator<Item = &'a (dyn StdError + 'static)> {
+        std::iter::successors(self.0.source(), |&cause| cause.source())
+    }
+}
+
+impl fmt::Display for Chain<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)?;
+        for cause in self.causes() {
+            match f.alternate() {
+                true => write!(f, "\n  caused by: {}", cause)?,
+                false => write!(f, ": {}", cause)?,
+            }
+        }
+        Ok(())
+    }
 }
 
 impl From<StdIoError> for Error {
@@ -47,3 +178,66 @@ impl From<StdParseIntError> for Error {
         Self::ParseIntError(pi_error.into())
     }
 }
+
+impl From<ureq::Error> for Error {
+    fn from(ureq_error: ureq::Error) -> Self {
+        match ureq_error {
+            ureq::Error::Status(status, response) => Self::HttpError(format!(
+                "{} responded with {} {}",
+                response.get_url(),
+                status,
+                response.status_text()
+            )),
+            ureq::Error::Transport(transport) => Self::HttpError(transport.to_string()),
+        }
+    }
+}
+
+#[test]
+fn test_chain() {
+    let err = Error::from("x".parse::<u8>().unwrap_err()).at_line(3);
+    assert_eq!(
+        err.chain().to_string(),
+        "Error on line 3: Parse IntError: invalid digit found in string"
+    );
+    assert_eq!(
+        format!("{:#}", err.chain()),
+        "Error on line 3\n  caused by: Parse IntError\n  caused by: invalid digit found in string"
+    );
+    assert_eq!(err.exit_code(), ExitCode::BadInput);
+
+    let err = Error::from(std::io::Error::new(
+        std::io::ErrorKind::NotFound,
+        "no input",
+    ));
+    assert_eq!(err.chain().to_string(), "IO Error: no input");
+    assert_eq!(err.exit_code(), ExitCode::Io);
+
+    let err =
+        Error::from(serde_json::from_str::<u8>("x").unwrap_err()).in_file(Path::new("a.json"));
+    assert_eq!(
+        err.chain().to_string(),
+        "Error in `a.json`: JSON Error: expected value at line 1 column 1"
+    );
+    assert_eq!(err.exit_code(), ExitCode::BadInput);
+
+    let text = "x bright white bags";
+    let err = SpanError::parse_within::<u8>(text, &text[..1]).unwrap_err();
+    assert_eq!(
+        err.chain().to_string(),
+        "line 1, column 1: unable to parse `x` as u8: Parse IntError: invalid digit found in string"
+    );
+    assert_eq!(
+        Error::UnimplementedDay(2020, 25).exit_code(),
+        ExitCode::Unsolved
+    );
+    assert_eq!(Error::InvalidYearArg(1999).exit_code(), ExitCode::Usage);
+    assert_eq!(
+        Error::InvalidDayOrPartArg(26, 1).exit_code(),
+        ExitCode::Usage
+    );
+    assert_eq!(
+        Error::InvalidState("".into()).exit_code(),
+        ExitCode::InternalState
+    );
+}
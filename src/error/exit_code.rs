@@ -0,0 +1,23 @@
+/// The process exit codes, so that scripts can tell kinds of failure apart.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ExitCode {
+    /// The run finished, but reported a failure: a failed day in `all`, a mismatch in `verify`, a
+    /// wrong answer from `submit`, or a regression from `bench`.
+    Failure = 1,
+    /// The command line was invalid, eg: an unknown flag, or a day outside 1-25.
+    Usage = 2,
+    /// Reading or writing a file, or talking to the website, failed.
+    Io = 3,
+    /// An input, manifest or other file couldn't be parsed, or didn't make sense.
+    BadInput = 4,
+    /// A solution reached a state that should be impossible, ie: a bug.
+    InternalState = 5,
+    /// The day or part hasn't been implemented, or its solution found no answer.
+    Unsolved = 6,
+}
+
+impl ExitCode {
+    pub fn exit(self) -> ! {
+        std::process::exit(self as i32)
+    }
+}
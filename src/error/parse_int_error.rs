@@ -1,8 +1,8 @@
-use thiserror::Error;
 use std::num;
+use thiserror::Error;
 
 #[derive(Debug, Error, PartialEq)]
-#[error("`num::ParseIntError`: {}", 0)]
+#[error(transparent)]
 pub struct ParseIntError(#[from] num::ParseIntError);
 
 // pub struct ParseIntError(num::ParseIntError);
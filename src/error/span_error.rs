@@ -0,0 +1,169 @@
+use std::fmt::Write;
+use std::ops::Range;
+use std::str::FromStr;
+
+use super::Error;
+
+/// A parse error, pinned to the part of a line that caused it.
+#[derive(Debug, thiserror::Error)]
+#[error("line {line}, column {}: {message}", columns.start + 1)]
+pub struct SpanError {
+    pub message: String,
+    /// The 1-indexed line number.
+    pub line: usize,
+    /// The 0-indexed range of characters within the line.
+    pub columns: Range<usize>,
+    /// The full line that contains the span.
+    pub source_line: String,
+    #[source]
+    pub cause: Option<Box<Error>>,
+}
+
+impl SpanError {
+    /// Points at the byte range `span` within `text`, which may contain several lines.  Line
+    /// numbers are relative to the start of `text`.
+    pub fn new(message: impl Into<String>, text: &str, span: Range<usize>) -> Self {
+        let start = floor_char_boundary(text, span.start);
+        let end = floor_char_boundary(text, span.end.max(start));
+        let line_start = text[..start].rfind('\n').map_or(0, |i| i + 1);
+        let line_end = text[start..].find('\n').map_or(text.len(), |i| start + i);
+        let column = text[line_start..start].chars().count();
+        Self {
+            message: message.into(),
+            line: text[..start].matches('\n').count() + 1,
+            columns: column..column + text[start..end.min(line_end)].chars().count(),
+            source_line: text[line_start..line_end]
+                .trim_end_matches('\r')
+                .to_string(),
+            cause: None,
+        }
+    }
+
+    /// Points at `part`, which must be a subslice of `text`.  Otherwise, the whole of `text` is
+    /// pointed at.
+    pub fn at(message: impl Into<String>, text: &str, part: &str) -> Self {
+        Self::new(message, text, subslice_range(text, part))
+    }
+
+    /// Parses `part`, a subslice of `text`, pointing any error at `part`.  Errors that already
+    /// carry a span are passed through unchanged.
+    pub fn parse_within<T>(text: &str, part: &str) -> Result<T, Error>
+    where
+        T: FromStr,
+        Error: From<T::Err>,
+    {
+        part.parse::<T>().map_err(|err| match Error::from(err) {
+            err @ Error::SpanError(_) => err,
+            err => Self {
+                cause: Some(Box::new(err)),
+                ..Self::at(
+                    format!("unable to parse `{}` as {}", part, short_type_name::<T>()),
+                    text,
+                    part,
+                )
+            }
+            .into(),
+        })
+    }
+
+    /// Renders the span underneath its source line, eg:
+    ///
+    /// ```text
+    /// error: expected a count
+    ///  --> line 3, column 5
+    ///   |
+    /// 3 | 1-3 a: abcde
+    ///   |     ^^^
+    /// ```
+    pub fn render(&self) -> String {
+        let gutter = " ".repeat(self.line.to_string().len());
+        let mut rendered = String::new();
+        // Writing to a `String` can't fail:
+        let _ = writeln!(rendered, "error: {}", self.message);
+        let _ = writeln!(
+            rendered,
+            "{}--> line {}, column {}",
+            gutter,
+            self.line,
+            self.columns.start + 1
+        );
+        let _ = writeln!(rendered, "{} |", gutter);
+        let _ = writeln!(rendered, "{} | {}", self.line, self.source_line);
+        let _ = writeln!(
+            rendered,
+            "{} | {}{}",
+            gutter,
+            " ".repeat(self.columns.start),
+            "^".repeat(self.columns.len().max(1))
+        );
+        rendered
+    }
+}
+
+fn floor_char_boundary(text: &str, index: usize) -> usize {
+    let mut index = index.min(text.len());
+    while !text.is_char_boundary(index) {
+        index -= 1;
+    }
+    index
+}
+
+fn subslice_range(text: &str, part: &str) -> Range<usize> {
+    let start = (part.as_ptr() as usize).wrapping_sub(text.as_ptr() as usize);
+    match start.checked_add(part.len()) {
+        Some(end) if end <= text.len() => start..end,
+        _ => 0..text.len(),
+    }
+}
+
+fn short_type_name<T>() -> &'static str {
+    let name = std::any::type_name::<T>();
+    name.rsplit("::").next().unwrap_or(name)
+}
+
+#[test]
+fn test_span() {
+    let text = "1-3 a: abcde\n1-3 b: cdefg\n";
+    let span = SpanError::new("expected a count", text, 15..18);
+    assert_eq!(span.line, 2);
+    assert_eq!(span.columns, 2..5);
+    assert_eq!(span.source_line, "1-3 b: cdefg");
+    assert_eq!(
+        span.render(),
+        "\
+error: expected a count
+ --> line 2, column 3
+  |
+2 | 1-3 b: cdefg
+  |   ^^^
+"
+    );
+    assert_eq!(
+        span.to_string(),
+        "line 2, column 3: expected a count".to_string()
+    );
+
+    // Spans past the end of a line are clamped to it:
+    let span = SpanError::new("expected a password", "1-3 a:", 6..20);
+    assert_eq!((span.line, span.columns.clone()), (1, 6..6));
+    assert!(span.render().ends_with("1 | 1-3 a:\n  |       ^\n"));
+}
+
+#[test]
+fn test_parse_within() {
+    let text = "3 bright white bags";
+    assert_eq!(
+        SpanError::parse_within::<u8>(text, &text[..1]).ok(),
+        Some(3)
+    );
+
+    let text = "x bright white bags";
+    match SpanError::parse_within::<u8>(text, &text[..1]) {
+        Err(Error::SpanError(span)) => {
+            assert_eq!(span.columns, 0..1);
+            assert_eq!(span.message, "unable to parse `x` as u8");
+            assert!(span.cause.is_some());
+        }
+        other => panic!("expected a span error, found: {:?}", other),
+    }
+}
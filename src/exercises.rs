@@ -1,3 +1,21 @@
+//! Each day's solution, along with its domain model.  The models can be parsed straight from a
+//! `&str`, and queried directly:
+//!
+//! ```
+//! use modulitos_2020::exercises::day_07::{Bag, BagsMap};
+//!
+//! let map = "shiny gold bags contain 2 dark red bags.\n\
+//!            dark red bags contain no other bags.\n"
+//!     .parse::<BagsMap>()?;
+//! assert_eq!(map.count_item_bags(&Bag::from("shiny gold"))?, 2);
+//! assert_eq!(map.count_containing_bags(&Bag::from("dark red"))?, 1);
+//! # Ok::<(), modulitos_2020::Error>(())
+//! ```
+//!
+//! Every day also implements `Solution`, which parses a whole puzzle `Input` and answers each part.
+
+use crate::solution::Solver;
+
 pub mod day_01;
 pub mod day_02;
 pub mod day_03;
@@ -5,3 +23,31 @@ pub mod day_04;
 pub mod day_05;
 pub mod day_06;
 pub mod day_07;
+
+/// The year that's used when none is given.  Its days are the modules above, while another year's
+/// days are in its own module (eg: `year_2021::day_01`).
+pub const DEFAULT_YEAR: usize = 2020;
+
+pub(crate) fn default_year() -> usize {
+    DEFAULT_YEAR
+}
+
+/// All implemented days: the default year's, then each other year's module, ordered by day.  Each
+/// new day needs to be registered here.
+pub static SOLUTIONS: &[&dyn Solver] = &[
+    &day_01::Day01,
+    &day_02::Day02,
+    &day_03::Day03,
+    &day_04::Day04,
+    &day_05::Day05,
+    &day_06::Day06,
+    &day_07::Day07,
+];
+
+/// Looks up the registered solution for the given year's day, if it has been implemented.
+pub fn find(year: usize, day: usize) -> Option<&'static dyn Solver> {
+    SOLUTIONS.iter().copied().find(|solver| {
+        let metadata = solver.metadata();
+        (metadata.year, metadata.day) == (year, day)
+    })
+}
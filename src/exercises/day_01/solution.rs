@@ -1,19 +1,18 @@
-use std::io::{BufRead, BufReader, Error as IoError, Read};
-
-use crate::convert_path_buf;
-use crate::option_ext::OptionExt;
+use crate::input::Input;
+#[cfg(test)]
+use crate::read_input;
+use crate::solution::{Metadata, Solution};
 use crate::vec_ext::VecExt;
 use crate::{Error, Result};
-use std::convert::TryInto;
+#[cfg(test)]
+use proptest::{collection::vec, prelude::*};
+#[cfg(test)]
 use std::path::PathBuf;
+use tracing::{debug, trace};
 
-fn get_receipts(buf_reader: BufReader<Box<dyn Read + '_>>) -> Result<Vec<Receipt>> {
-    Ok(buf_reader
-        .lines()
-        .collect::<Result<Vec<String>, _>>()? // elided io::IoError
-        .into_iter()
-        .map(|value| value.parse::<u32>())
-        .collect::<Result<Vec<u32>, _>>()? // elided num::ParseIntError
+fn get_receipts(input: &Input) -> Result<Vec<Receipt>> {
+    Ok(input
+        .integers::<u32>()?
         .sorted()
         .into_iter()
         .enumerate()
@@ -21,30 +20,50 @@ fn get_receipts(buf_reader: BufReader<Box<dyn Read + '_>>) -> Result<Vec<Receipt
         .collect())
 }
 
-fn search_combinations(receipts: &Vec<Receipt>, third: Option<&Receipt>) -> Result<Option<u32>> {
-    let mut end = receipts.len() - 1;
+fn search_combinations(receipts: &[Receipt], third: Option<&Receipt>) -> Result<Option<u32>> {
+    let mut end = match receipts.len() {
+        0 => return Ok(None),
+        len => len - 1,
+    };
     let (mut start, extra_receipt_value) = match third {
         Some(receipt) => (std::cmp::min(receipt.id + 1, end), receipt.value),
         None => (0, 0),
     };
+    // Summing as u64, so that large receipts can't overflow:
+    let sum = |start: &Receipt, end: &Receipt| {
+        u64::from(start.value) + u64::from(end.value) + u64::from(extra_receipt_value)
+    };
     loop {
         match (receipts.get(start), receipts.get(end)) {
             (Some(start_receipt), Some(end_receipt)) if start_receipt == end_receipt => {
+                trace!(start, end, extra_receipt_value, "the pointers met");
                 return Ok(None);
             }
-            (Some(start_receipt), Some(end_receipt))
-                if start_receipt.value + end_receipt.value + extra_receipt_value > 2020 =>
-            {
+            (Some(start_receipt), Some(end_receipt)) if sum(start_receipt, end_receipt) > 2020 => {
+                trace!(
+                    start,
+                    end,
+                    sum = sum(start_receipt, end_receipt),
+                    "moving end down"
+                );
                 end -= 1;
             }
-            (Some(start_receipt), Some(end_receipt))
-                if start_receipt.value + end_receipt.value + extra_receipt_value < 2020 =>
-            {
+            (Some(start_receipt), Some(end_receipt)) if sum(start_receipt, end_receipt) < 2020 => {
+                trace!(
+                    start,
+                    end,
+                    sum = sum(start_receipt, end_receipt),
+                    "moving start up"
+                );
                 start += 1;
             }
-            (Some(start_receipt), Some(end_receipt))
-                if start_receipt.value + end_receipt.value + extra_receipt_value == 2020 =>
-            {
+            (Some(start_receipt), Some(end_receipt)) if sum(start_receipt, end_receipt) == 2020 => {
+                debug!(
+                    start = start_receipt.value,
+                    end = end_receipt.value,
+                    extra_receipt_value,
+                    "found receipts summing to 2020"
+                );
                 return Ok(Some(start_receipt.value * end_receipt.value));
             }
             _ => {
@@ -57,30 +76,42 @@ fn search_combinations(receipts: &Vec<Receipt>, third: Option<&Receipt>) -> Resu
     }
 }
 
-pub fn part_1(buf_reader: BufReader<Box<dyn Read + '_>>) -> Result<Option<u32>> {
-    let receipts = get_receipts(buf_reader)?;
-    search_combinations(&receipts, None)
-}
+#[derive(Debug)]
+pub struct Day01;
 
-pub fn part_2(buf_reader: BufReader<Box<dyn Read + '_>>) -> Result<Option<u32>> {
-    let receipts = get_receipts(buf_reader)?;
-    let res = receipts.iter().find_map(|receipt| {
-        // println!("iterating over receipt: {:?}", receipt);
-        match search_combinations(&receipts, Some(receipt)) {
-            Ok(Some(product)) => Some(product * receipt.value),
-            Ok(None) => None,
-
-            // TODO: We can avoid this panic, and keep a declarative style, by using `try_find`,
-            // only available on nightly:
-            // https://doc.rust-lang.org/std/iter/trait.Iterator.html#method.try_find
-            Err(err) => panic!("error: {:?}", err),
+impl Solution for Day01 {
+    type Model = Vec<Receipt>;
+    type Part1 = Option<u32>;
+    type Part2 = Option<u32>;
+
+    fn metadata(&self) -> Metadata {
+        Metadata {
+            year: 2020,
+            day: 1,
+            title: "Report Repair",
         }
-    });
-    Ok(res)
+    }
+
+    fn parse(&self, input: &Input) -> Result<Vec<Receipt>> {
+        get_receipts(input)
+    }
+
+    fn part_1(&self, receipts: &Vec<Receipt>) -> Result<Option<u32>> {
+        search_combinations(receipts, None)
+    }
+
+    fn part_2(&self, receipts: &Vec<Receipt>) -> Result<Option<u32>> {
+        for receipt in receipts {
+            if let Some(product) = search_combinations(receipts, Some(receipt))? {
+                return Ok(Some(product * receipt.value));
+            }
+        }
+        Ok(None)
+    }
 }
 
 #[derive(Debug, PartialOrd, PartialEq, Ord, Eq)]
-struct Receipt {
+pub struct Receipt {
     value: u32,
     id: usize,
 }
@@ -94,10 +125,35 @@ impl From<(usize, u32)> for Receipt {
     }
 }
 
+impl Receipt {
+    /// The expense's amount.
+    pub fn value(&self) -> u32 {
+        self.value
+    }
+
+    /// The receipt's position once sorted by value, which tells apart receipts with the same value.
+    pub fn id(&self) -> usize {
+        self.id
+    }
+}
+
+#[test]
+fn test_receipt_queries() -> Result<()> {
+    let receipts = Day01.parse(&Input::from("1721\n979\n"))?;
+    assert_eq!(
+        receipts
+            .iter()
+            .map(|receipt| (receipt.id(), receipt.value()))
+            .collect::<Vec<(usize, u32)>>(),
+        vec![(0, 979), (1, 1721)]
+    );
+    Ok(())
+}
+
 #[test]
 fn test_part_1_example() -> Result<()> {
-    let p = Some(PathBuf::from("./src/exercises/day_01/test.txt"));
-    let res = part_1(convert_path_buf(p)?)?;
+    let p = Some(PathBuf::from("./inputs/day_01.example.txt"));
+    let res = Day01.part_1(&Day01.parse(&read_input(p)?)?)?;
 
     assert_eq!(res, Some(514579));
     Ok(())
@@ -105,8 +161,8 @@ fn test_part_1_example() -> Result<()> {
 
 #[test]
 fn test_part_1() -> Result<()> {
-    let p = Some(PathBuf::from("./src/exercises/day_01/receipts.txt"));
-    let res = part_1(convert_path_buf(p)?)?;
+    let p = Some(PathBuf::from("./inputs/day_01.txt"));
+    let res = Day01.part_1(&Day01.parse(&read_input(p)?)?)?;
     assert_eq!(res, Some(876459));
 
     Ok(())
@@ -114,8 +170,8 @@ fn test_part_1() -> Result<()> {
 
 #[test]
 fn test_part_2_example() -> Result<()> {
-    let p = Some(PathBuf::from("./src/exercises/day_01/test.txt"));
-    let res = part_2(convert_path_buf(p)?)?;
+    let p = Some(PathBuf::from("./inputs/day_01.example.txt"));
+    let res = Day01.part_2(&Day01.parse(&read_input(p)?)?)?;
 
     assert_eq!(res, Some(241861950));
     Ok(())
@@ -123,9 +179,78 @@ fn test_part_2_example() -> Result<()> {
 
 #[test]
 fn test_part_2() -> Result<()> {
-    let p = Some(PathBuf::from("./src/exercises/day_01/receipts.txt"));
-    let res = part_2(convert_path_buf(p)?)?;
+    let p = Some(PathBuf::from("./inputs/day_01.txt"));
+    let res = Day01.part_2(&Day01.parse(&read_input(p)?)?)?;
 
     assert_eq!(res, Some(116168640));
     Ok(())
 }
+
+#[test]
+fn test_no_combination() -> Result<()> {
+    for text in &["", "2020\n", "4294967295\n4294967295\n4294967295\n"] {
+        let receipts = Day01.parse(&Input::from(*text))?;
+        assert_eq!(Day01.part_1(&receipts)?, None);
+        assert_eq!(Day01.part_2(&receipts)?, None);
+    }
+    Ok(())
+}
+
+/// Random expense reports, which always contain a pair and a triple of entries that sum to 2020,
+/// in any order.
+#[cfg(test)]
+fn arb_expenses() -> impl Strategy<Value = Vec<u32>> {
+    (
+        0..=2020_u32,
+        0..=2020_u32,
+        0..=2020_u32,
+        vec(0..=2020_u32, 0..20),
+    )
+        .prop_flat_map(|(a, x, y, others)| {
+            let y = y % (2020 - x + 1);
+            let mut expenses = vec![a, 2020 - a, x, y, 2020 - x - y];
+            expenses.extend(others);
+            Just(expenses).prop_shuffle()
+        })
+}
+
+#[cfg(test)]
+fn to_input(expenses: &[u32]) -> Input {
+    Input::new(
+        expenses
+            .iter()
+            .map(|expense| format!("{}\n", expense))
+            .collect::<String>(),
+    )
+}
+
+#[cfg(test)]
+proptest! {
+    #[test]
+    fn prop_part_1_sums_to_2020(expenses in arb_expenses()) {
+        let product = Day01.part_1(&Day01.parse(&to_input(&expenses))?)?;
+        let product = product.expect("a pair that sums to 2020 was planted");
+        let found = (0..expenses.len()).any(|i| {
+            (i + 1..expenses.len()).any(|j| {
+                expenses[i] + expenses[j] == 2020 && expenses[i] * expenses[j] == product
+            })
+        });
+        prop_assert!(found, "{} isn't the product of a pair that sums to 2020", product);
+    }
+
+    #[test]
+    fn prop_part_2_sums_to_2020(expenses in arb_expenses()) {
+        let product = Day01.part_2(&Day01.parse(&to_input(&expenses))?)?;
+        let product = product.expect("a triple that sums to 2020 was planted");
+        let n = expenses.len();
+        let found = (0..n).any(|i| {
+            (i + 1..n).any(|j| {
+                (j + 1..n).any(|k| {
+                    expenses[i] + expenses[j] + expenses[k] == 2020
+                        && expenses[i] * expenses[j] * expenses[k] == product
+                })
+            })
+        });
+        prop_assert!(found, "{} isn't the product of a triple that sums to 2020", product);
+    }
+}
@@ -1,9 +1,14 @@
-use std::io::{BufRead, BufReader, Read};
-
-use crate::convert_path_buf;
+use crate::error::SpanError;
+use crate::input::Input;
+#[cfg(test)]
+use crate::read_input;
+use crate::solution::{Metadata, Solution};
 use crate::{Error, Result};
+#[cfg(test)]
+use proptest::{collection::vec, prelude::*};
 use regex::Regex;
 use std::ops::Range;
+#[cfg(test)]
 use std::path::PathBuf;
 use std::str::FromStr;
 
@@ -14,7 +19,7 @@ trait Policy {
 
 #[derive(Debug, Eq, PartialEq)]
 struct Policy1 {
-    range: Range<u8>,
+    range: Range<usize>,
     char: char,
 }
 
@@ -22,8 +27,8 @@ impl Policy for Policy1 {
     fn new(first: u8, second: u8, char: char) -> Self {
         Self {
             range: std::ops::Range {
-                start: first,
-                end: second + 1, // the "end" of a range is exclusive
+                start: usize::from(first),
+                end: usize::from(second) + 1, // the "end" of a range is exclusive
             },
             char,
         }
@@ -31,39 +36,82 @@ impl Policy for Policy1 {
 
     fn is_valid(&self, pw: &Password) -> bool {
         self.range
-            .contains(&(pw.chars().filter(|char| char == &self.char).count() as u8))
+            .contains(&pw.chars().filter(|char| char == &self.char).count())
     }
 }
 
 struct Policy2 {
-    pos_1: u8,
-    pos_2: u8,
+    // `None` for a position of 0, which can never match, since positions are 1-indexed.
+    pos_1: Option<usize>,
+    pos_2: Option<usize>,
     char: char,
 }
 
 impl Policy for Policy2 {
     fn new(first: u8, second: u8, char: char) -> Self {
         Self {
-            pos_1: first - 1,  // normalize from 1-index to 0-index
-            pos_2: second - 1,
+            pos_1: usize::from(first).checked_sub(1), // normalize from 1-index to 0-index
+            pos_2: usize::from(second).checked_sub(1),
             char,
         }
     }
 
     fn is_valid(&self, pw: &Password) -> bool {
-        match (&pw.chars().nth(usize::from(self.pos_1)), &pw.chars().nth(usize::from(self.pos_2))) {
-            (Some(first), Some(second)) => (first == &self.char) ^ (second == &self.char),
-            _ => false
+        let char_at = |pos: Option<usize>| pos.and_then(|pos| pw.chars().nth(pos));
+        match (char_at(self.pos_1), char_at(self.pos_2)) {
+            (Some(first), Some(second)) => (first == self.char) ^ (second == self.char),
+            _ => false,
         }
     }
 }
 
+/// The policy's numbers and character, which each part's `Policy` interprets differently.
 #[derive(Debug, Eq, PartialEq)]
-struct PolicyWithPassword<P: Policy>((P, Password));
+pub struct PolicyWithPassword {
+    first: u8,
+    second: u8,
+    char: char,
+    password: Password,
+}
 
 type Password = String;
 
-impl<P: Policy> FromStr for PolicyWithPassword<P> {
+impl PolicyWithPassword {
+    /// The policy's first number: the lowest count in part 1, or the first position in part 2.
+    pub fn first(&self) -> u8 {
+        self.first
+    }
+
+    /// The policy's second number: the highest count in part 1, or the second position in part 2.
+    pub fn second(&self) -> u8 {
+        self.second
+    }
+
+    pub fn char(&self) -> char {
+        self.char
+    }
+
+    pub fn password(&self) -> &str {
+        &self.password
+    }
+
+    /// Whether the password contains the policy's character between `first` and `second` times.
+    pub fn is_valid_part_1(&self) -> bool {
+        self.is_valid::<Policy1>()
+    }
+
+    /// Whether the policy's character is at exactly one of the 1-indexed positions `first` and
+    /// `second` in the password.
+    pub fn is_valid_part_2(&self) -> bool {
+        self.is_valid::<Policy2>()
+    }
+
+    fn is_valid<P: Policy>(&self) -> bool {
+        P::new(self.first, self.second, self.char).is_valid(&self.password)
+    }
+}
+
+impl FromStr for PolicyWithPassword {
     type Err = Error;
 
     fn from_str(s: &str) -> Result<Self> {
@@ -85,68 +133,119 @@ impl<P: Policy> FromStr for PolicyWithPassword<P> {
             .unwrap();
         }
 
-        let caps = RE.captures(s).unwrap();
-
-        let policy = P::new(
-            caps["range_low"].parse()?,
-            caps["range_high"].parse::<u8>()?,
-            caps["char"].parse()?,
-        );
-        let password = caps["password"].parse()?;
+        let caps = RE.captures(s).ok_or_else(|| {
+            SpanError::at("expected a policy and password, eg: `1-3 a: abcde`", s, s)
+        })?;
 
-        Ok(Self((policy, password)))
+        Ok(Self {
+            first: SpanError::parse_within(s, &caps["range_low"])?,
+            second: SpanError::parse_within(s, &caps["range_high"])?,
+            char: SpanError::parse_within(s, &caps["char"])?,
+            password: SpanError::parse_within(s, &caps["password"])?,
+        })
     }
 }
 
-fn get_policies<P: Policy>(
-    buf_reader: BufReader<Box<dyn Read + '_>>,
-) -> Result<Vec<PolicyWithPassword<P>>> {
-    buf_reader
-        .lines()
-        .into_iter()
-        .collect::<Result<Vec<String>, _>>()?
-        .into_iter()
-        .map(|line| PolicyWithPassword::from_str(&line))
-        .collect()
-}
+#[derive(Debug)]
+pub struct Day02;
 
-pub fn part_1(buf_reader: BufReader<Box<dyn Read + '_>>) -> Result<u32> {
-    let policies_with_passwords: Vec<PolicyWithPassword<Policy1>> = get_policies(buf_reader)?;
+impl Solution for Day02 {
+    type Model = Vec<PolicyWithPassword>;
+    type Part1 = usize;
+    type Part2 = usize;
 
-    Ok(policies_with_passwords
-        .into_iter()
-        .filter(|PolicyWithPassword((policy, password))| policy.is_valid(password))
-        .count() as u32)
-}
-pub fn part_2(buf_reader: BufReader<Box<dyn Read + '_>>) -> Result<u32> {
-    let policies_with_passwords: Vec<PolicyWithPassword<Policy2>> = get_policies(buf_reader)?;
+    fn metadata(&self) -> Metadata {
+        Metadata {
+            year: 2020,
+            day: 2,
+            title: "Password Philosophy",
+        }
+    }
 
-    Ok(policies_with_passwords
-        .into_iter()
-        .filter(|PolicyWithPassword((policy, password))| policy.is_valid(password))
-        .count() as u32)
+    fn parse(&self, input: &Input) -> Result<Vec<PolicyWithPassword>> {
+        input.parse_lines()
+    }
+
+    fn part_1(&self, policies_with_passwords: &Vec<PolicyWithPassword>) -> Result<usize> {
+        Ok(policies_with_passwords
+            .iter()
+            .filter(|policy_with_password| policy_with_password.is_valid_part_1())
+            .count())
+    }
+
+    fn part_2(&self, policies_with_passwords: &Vec<PolicyWithPassword>) -> Result<usize> {
+        Ok(policies_with_passwords
+            .iter()
+            .filter(|policy_with_password| policy_with_password.is_valid_part_2())
+            .count())
+    }
 }
 
 #[test]
 fn test_parser() -> Result<()> {
-    let res = "1-3 a: abcde".parse::<PolicyWithPassword<Policy1>>()?;
+    let res = "1-3 a: abcde".parse::<PolicyWithPassword>()?;
     assert_eq!(
         res,
-        PolicyWithPassword((
-            Policy1 {
-                range: Range { start: 1, end: 4 },
-                char: 'a'
-            },
-            "abcde".into()
-        ))
+        PolicyWithPassword {
+            first: 1,
+            second: 3,
+            char: 'a',
+            password: "abcde".into()
+        }
     );
+    assert_eq!(
+        Policy1::new(res.first, res.second, res.char),
+        Policy1 {
+            range: Range { start: 1, end: 4 },
+            char: 'a'
+        }
+    );
+    Ok(())
+}
+
+#[test]
+fn test_queries() -> Result<()> {
+    let policy = "1-3 b: cdefg".parse::<PolicyWithPassword>()?;
+    assert_eq!(
+        (
+            policy.first(),
+            policy.second(),
+            policy.char(),
+            policy.password()
+        ),
+        (1, 3, 'b', "cdefg")
+    );
+    assert!(!policy.is_valid_part_1());
+    assert!(!policy.is_valid_part_2());
+
+    let policy = "1-3 a: abcde".parse::<PolicyWithPassword>()?;
+    assert!(policy.is_valid_part_1());
+    assert!(policy.is_valid_part_2());
+    Ok(())
+}
+
+#[test]
+fn test_parser_error() -> Result<()> {
+    let err = "1-3 a abcde".parse::<PolicyWithPassword>().unwrap_err();
+    assert_eq!(err.span().map(|span| span.columns.clone()), Some(0..11));
+    Ok(())
+}
+
+#[test]
+fn test_policy_bounds() -> Result<()> {
+    let policy = "0-255 a: a".parse::<PolicyWithPassword>()?;
+    assert!(policy.is_valid_part_1());
+    assert!(!policy.is_valid_part_2());
+
+    let policy = "0-1 a: a".parse::<PolicyWithPassword>()?;
+    assert!(!policy.is_valid_part_2());
     Ok(())
 }
 
 #[test]
 fn test_part_1_example() -> Result<()> {
-    let p = Some(PathBuf::from("./src/exercises/day_02/test.txt"));
-    let res = part_1(convert_path_buf(p)?)?;
+    let p = Some(PathBuf::from("./inputs/day_02.example.txt"));
+    let res = Day02.part_1(&Day02.parse(&read_input(p)?)?)?;
 
     assert_eq!(res, 2);
     Ok(())
@@ -154,8 +253,8 @@ fn test_part_1_example() -> Result<()> {
 
 #[test]
 fn test_part_1() -> Result<()> {
-    let p = Some(PathBuf::from("./src/exercises/day_02/passwords.txt"));
-    let res = part_1(convert_path_buf(p)?)?;
+    let p = Some(PathBuf::from("./inputs/day_02.txt"));
+    let res = Day02.part_1(&Day02.parse(&read_input(p)?)?)?;
 
     assert_eq!(res, 550);
     Ok(())
@@ -163,8 +262,8 @@ fn test_part_1() -> Result<()> {
 
 #[test]
 fn test_part_2_example() -> Result<()> {
-    let p = Some(PathBuf::from("./src/exercises/day_02/test.txt"));
-    let res = part_2(convert_path_buf(p)?)?;
+    let p = Some(PathBuf::from("./inputs/day_02.example.txt"));
+    let res = Day02.part_2(&Day02.parse(&read_input(p)?)?)?;
 
     assert_eq!(res, 1);
     Ok(())
@@ -172,9 +271,64 @@ fn test_part_2_example() -> Result<()> {
 
 #[test]
 fn test_part_2() -> Result<()> {
-    let p = Some(PathBuf::from("./src/exercises/day_02/passwords.txt"));
-    let res = part_2(convert_path_buf(p)?)?;
+    let p = Some(PathBuf::from("./inputs/day_02.txt"));
+    let res = Day02.part_2(&Day02.parse(&read_input(p)?)?)?;
 
     assert_eq!(res, 634);
     Ok(())
 }
+
+/// Random policies over a small alphabet, so that passwords often contain the policy's character.
+#[cfg(test)]
+fn arb_policy_with_password() -> impl Strategy<Value = PolicyWithPassword> {
+    (1..=10_u8, 0..=5_u8, "[a-e]", "[a-e]{1,15}").prop_map(|(first, extra, char, password)| {
+        PolicyWithPassword {
+            first,
+            second: first + extra,
+            char: char.chars().next().unwrap(),
+            password,
+        }
+    })
+}
+
+#[cfg(test)]
+proptest! {
+    #[test]
+    fn prop_parse_round_trips(policy in arb_policy_with_password()) {
+        let line = format!(
+            "{}-{} {}: {}",
+            policy.first, policy.second, policy.char, policy.password
+        );
+        prop_assert_eq!(line.parse::<PolicyWithPassword>()?, policy);
+    }
+
+    #[test]
+    fn prop_parts_match_naive_checks(policies in vec(arb_policy_with_password(), 0..20)) {
+        let input = Input::new(
+            policies
+                .iter()
+                .map(|p| format!("{}-{} {}: {}\n", p.first, p.second, p.char, p.password))
+                .collect::<String>(),
+        );
+        let model = Day02.parse(&input)?;
+        let occurs_within_range = |p: &PolicyWithPassword| {
+            let count = p.password.chars().filter(|&c| c == p.char).count();
+            usize::from(p.first) <= count && count <= usize::from(p.second)
+        };
+        let occurs_at_one_position = |p: &PolicyWithPassword| {
+            let at = |position: u8| p.password.chars().nth(usize::from(position) - 1);
+            match (at(p.first), at(p.second)) {
+                (Some(first), Some(second)) => (first == p.char) != (second == p.char),
+                _ => false,
+            }
+        };
+        prop_assert_eq!(
+            Day02.part_1(&model)?,
+            policies.iter().filter(|p| occurs_within_range(p)).count()
+        );
+        prop_assert_eq!(
+            Day02.part_2(&model)?,
+            policies.iter().filter(|p| occurs_at_one_position(p)).count()
+        );
+    }
+}
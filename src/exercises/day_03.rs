@@ -1,4 +1,3 @@
-pub use solution::part_1;
-pub use solution::part_2;
+pub use solution::{Area, Day03};
 
 mod solution;
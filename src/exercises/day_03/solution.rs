@@ -1,8 +1,11 @@
-use std::io::{BufReader, Read};
-
-use crate::convert_path_buf;
+use crate::input::{Grid, Input};
+#[cfg(test)]
+use crate::read_input;
+use crate::solution::{Metadata, Solution};
 use crate::{Error, Result};
-use std::collections::BTreeMap;
+#[cfg(test)]
+use proptest::{collection::vec, prelude::*};
+#[cfg(test)]
 use std::path::PathBuf;
 use std::str::FromStr;
 
@@ -19,57 +22,68 @@ enum Land {
 }
 
 impl Land {
-    fn from_byte(b: &u8) -> Result<Self> {
-        match b {
-            b'#' => Ok(Land::Tree),
-            b'.' => Ok(Land::Open),
-            b => Err(Error::InvalidState(format!(
+    fn from_char(c: char) -> Result<Self> {
+        match c {
+            '#' => Ok(Land::Tree),
+            '.' => Ok(Land::Open),
+            c => Err(Error::InvalidInput(format!(
                 "Invalid input for Land: {}",
-                b
+                c
             ))),
         }
     }
 }
 
-struct Area {
+/// The map of open squares and trees, which repeats indefinitely to the right.
+#[derive(Debug)]
+pub struct Area {
     width: usize,
     height: usize,
-    map: BTreeMap<Coordinate, Land>,
+    grid: Grid<Land>,
+}
+
+impl From<Grid<Land>> for Area {
+    fn from(grid: Grid<Land>) -> Self {
+        Self {
+            width: grid.width(),
+            height: grid.height(),
+            grid,
+        }
+    }
 }
 
 impl FromStr for Area {
     type Err = Error;
+
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        // Traverse through the string to determine the extents of the Area:
-        let height = s.lines().count();
-        let width = s.lines().next().unwrap_or("").len();
-        if !s.lines().all(|line| line.len() == width) {
-            return Err(Error::InvalidState(format!(
-                "all lines in input must have the same length. Currently measured length: {}",
-                width
-            )));
+        Ok(Input::from(s).grid(Land::from_char)?.into())
+    }
+}
+
+impl Area {
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// Whether there is a tree at the coordinate.  Because the area repeats to the right, only
+    /// coordinates below the area are off the map.
+    pub fn is_tree(&self, x: usize, y: usize) -> Option<bool> {
+        if self.width == 0 {
+            return None;
         }
+        self.grid
+            .get(x % self.width, y)
+            .map(|land| matches!(land, Land::Tree))
+    }
 
-        let map = s
-            .lines()
-            .enumerate()
-            // TODO: how to flat_map when the closure returns a Result<Iterator<_>> ?
-            .map(|(y, line)| {
-                line.as_bytes()
-                    .into_iter()
-                    .enumerate()
-                    .map(|(x, b)| Ok((Coordinate { x, y }, Land::from_byte(b)?)))
-                    .collect::<Result<Vec<(Coordinate, Land)>>>()
-            })
-            .collect::<Result<Vec<Vec<(Coordinate, Land)>>>>()?
-            .into_iter()
-            .fold(BTreeMap::new(), |mut map, row| {
-                row.into_iter().for_each(|(coord, land)| {
-                    map.insert(coord, land);
-                });
-                map
-            });
-        Ok(Self { width, height, map })
+    /// Counts the trees encountered on the way down from the top-left corner, moving `right` and
+    /// `down` on each step.  Moving down by zero never leaves the area, so it's an error.
+    pub fn count_trees(&self, right: usize, down: usize) -> Result<u64> {
+        Simulation::new(self).run(right, down)
     }
 }
 
@@ -80,29 +94,25 @@ struct Simulation<'a> {
 
 #[derive(Debug)]
 struct Movement {
-    dx: i32, // abs value must be less than width of Area.
-    dy: i32, // abs value must be less than height of Area
+    dx: usize,
+    dy: usize,
 }
 
-impl Movement {}
-
 impl<'a> Simulation<'a> {
-
     fn new(area: &'a Area) -> Self {
         Self {
             area,
-            user: Coordinate {x: 0, y: 0}
+            user: Coordinate { x: 0, y: 0 },
         }
     }
-    fn create_movement(&self, dx: i32, dy: i32) -> Result<Movement> {
+    fn create_movement(&self, dx: usize, dy: usize) -> Result<Movement> {
         let movement = Movement { dx, dy };
 
         // validate that the Movement makes sense in the context of the Area:
-        if movement.dx + (self.area.width as i32) < 0 || movement.dy + (self.area.height as i32) < 0
-        {
-            return Err(Error::InvalidState(format!(
-                "Movement displacements exceed the Area's width: {}, height: {}, movement: {:?}",
-                self.area.width, self.area.height, movement
+        if movement.dy == 0 {
+            return Err(Error::InvalidInput(format!(
+                "Movement must go down the Area, or it never finishes: {:?}",
+                movement
             )));
         }
 
@@ -116,16 +126,20 @@ impl<'a> Simulation<'a> {
     // User can only move off the map in the y direction. In the x direction, they just loop around.
     //
     fn take_move(&mut self, movement: &Movement) -> Option<&Land> {
-        self.user.x =
-            (self.user.x + (movement.dx + (self.area.width as i32)) as usize) % self.area.width;
-        self.user.y = ((self.user.y as i32) + (movement.dy)) as usize;
+        // Both terms are below the width, so the sum can't overflow:
+        self.user.x = (self.user.x + movement.dx % self.area.width) % self.area.width;
+        self.user.y = self.user.y.checked_add(movement.dy)?;
 
-        self.area.map.get(&self.user)
+        self.area.grid.get(self.user.x, self.user.y)
     }
 
-    fn run(mut self, dx: i32, dy: i32) -> Result<u64> {
+    fn run(mut self, dx: usize, dy: usize) -> Result<u64> {
         let mut trees = 0;
         let movement = self.create_movement(dx, dy)?;
+        if self.area.width == 0 {
+            // There are no squares to move through, so there are no trees:
+            return Ok(0);
+        }
         loop {
             match self.take_move(&movement) {
                 Some(&Land::Tree) => trees += 1,
@@ -139,35 +153,69 @@ impl<'a> Simulation<'a> {
     }
 }
 
-pub fn part_1(mut buf_reader: BufReader<Box<dyn Read + '_>>) -> Result<u64> {
-    let mut input = String::new();
-    buf_reader.read_to_string(&mut input)?;
-    let area = input.parse::<Area>()?;
-    let simulation = Simulation::new(&area);
-    simulation.run(3, 1)
+#[derive(Debug)]
+pub struct Day03;
+
+impl Solution for Day03 {
+    type Model = Area;
+    type Part1 = u64;
+    type Part2 = u64;
+
+    fn metadata(&self) -> Metadata {
+        Metadata {
+            year: 2020,
+            day: 3,
+            title: "Toboggan Trajectory",
+        }
+    }
+
+    fn parse(&self, input: &Input) -> Result<Area> {
+        Ok(input.grid(Land::from_char)?.into())
+    }
+
+    fn part_1(&self, area: &Area) -> Result<u64> {
+        area.count_trees(3, 1)
+    }
+
+    fn part_2(&self, area: &Area) -> Result<u64> {
+        Ok(vec![
+            area.count_trees(1, 1),
+            area.count_trees(3, 1),
+            area.count_trees(5, 1),
+            area.count_trees(7, 1),
+            area.count_trees(1, 2),
+        ]
+        .into_iter()
+        .collect::<Result<Vec<u64>>>()?
+        .into_iter()
+        .product())
+    }
 }
 
-pub fn part_2(mut buf_reader: BufReader<Box<dyn Read + '_>>) -> Result<u64> {
-    let mut input = String::new();
-    buf_reader.read_to_string(&mut input)?;
-    let area = input.parse::<Area>()?;
-    Ok(vec![
-        Simulation::new(&area).run(1, 1),
-        Simulation::new(&area).run(3, 1),
-        Simulation::new(&area).run(5, 1),
-        Simulation::new(&area).run(7, 1),
-        Simulation::new(&area).run(1, 2),
-    ]
-    .into_iter()
-    .collect::<Result<Vec<u64>>>()?
-    .into_iter()
-    .product())
+#[test]
+fn test_area_queries() -> Result<()> {
+    let area = "..#\n#..\n.#.\n".parse::<Area>()?;
+    assert_eq!((area.width(), area.height()), (3, 3));
+    assert_eq!(area.is_tree(2, 0), Some(true));
+    assert_eq!(area.is_tree(5, 0), Some(true)); // the area repeats to the right
+    assert_eq!(area.is_tree(1, 0), Some(false));
+    assert_eq!(area.is_tree(0, 3), None);
+    assert_eq!(area.count_trees(1, 1)?, 0);
+    assert_eq!(area.count_trees(2, 1)?, 1);
+    assert!(area.count_trees(1, 0).is_err());
+    assert_eq!(area.count_trees(0, usize::MAX)?, 0);
+    assert_eq!(area.count_trees(usize::MAX, 1)?, 1);
+    assert_eq!(area.count_trees(usize::MAX, usize::MAX)?, 0);
+
+    let empty = "\n\n".parse::<Area>()?;
+    assert_eq!(empty.count_trees(3, 1)?, 0);
+    Ok(())
 }
 
 #[test]
 fn test_part_1_example() -> Result<()> {
-    let p = Some(PathBuf::from("./src/exercises/day_03/test.txt"));
-    let res = part_1(convert_path_buf(p)?)?;
+    let p = Some(PathBuf::from("./inputs/day_03.example.txt"));
+    let res = Day03.part_1(&Day03.parse(&read_input(p)?)?)?;
 
     assert_eq!(res, 7);
     Ok(())
@@ -175,16 +223,16 @@ fn test_part_1_example() -> Result<()> {
 
 #[test]
 fn test_part_1() -> Result<()> {
-    let p = Some(PathBuf::from("./src/exercises/day_03/area.txt"));
-    let res = part_1(convert_path_buf(p)?)?;
+    let p = Some(PathBuf::from("./inputs/day_03.txt"));
+    let res = Day03.part_1(&Day03.parse(&read_input(p)?)?)?;
 
     assert_eq!(res, 176);
     Ok(())
 }
 #[test]
 fn test_part_2_example() -> Result<()> {
-    let p = Some(PathBuf::from("./src/exercises/day_03/test.txt"));
-    let res = part_2(convert_path_buf(p)?)?;
+    let p = Some(PathBuf::from("./inputs/day_03.example.txt"));
+    let res = Day03.part_2(&Day03.parse(&read_input(p)?)?)?;
 
     assert_eq!(res, 336);
     Ok(())
@@ -192,9 +240,50 @@ fn test_part_2_example() -> Result<()> {
 
 #[test]
 fn test_part_2() -> Result<()> {
-    let p = Some(PathBuf::from("./src/exercises/day_03/area.txt"));
-    let res = part_2(convert_path_buf(p)?)?;
+    let p = Some(PathBuf::from("./inputs/day_03.txt"));
+    let res = Day03.part_2(&Day03.parse(&read_input(p)?)?)?;
 
     assert_eq!(res, 5872458240);
     Ok(())
 }
+
+/// Random rectangular maps, as rows of whether each square has a tree.
+#[cfg(test)]
+fn arb_rows() -> impl Strategy<Value = Vec<Vec<bool>>> {
+    (1..=12_usize, 1..=30_usize)
+        .prop_flat_map(|(width, height)| vec(vec(any::<bool>(), width), height))
+}
+
+/// Counts the trees on each step down the slope, without counting the starting square.
+#[cfg(test)]
+fn naive_count_trees(rows: &[Vec<bool>], right: usize, down: usize) -> u64 {
+    (1..)
+        .map(|step| (step * right, step * down))
+        .take_while(|&(_, y)| y < rows.len())
+        .filter(|&(x, y)| rows[y][x % rows[y].len()])
+        .count() as u64
+}
+
+#[cfg(test)]
+proptest! {
+    #[test]
+    fn prop_count_trees_matches_naive_count(rows in arb_rows()) {
+        let input = Input::new(
+            rows.iter()
+                .map(|row| {
+                    row.iter().map(|&tree| if tree { '#' } else { '.' }).collect::<String>() + "\n"
+                })
+                .collect::<String>(),
+        );
+        let area = Day03.parse(&input)?;
+        prop_assert_eq!((area.width(), area.height()), (rows[0].len(), rows.len()));
+        prop_assert_eq!(Day03.part_1(&area)?, naive_count_trees(&rows, 3, 1));
+        prop_assert_eq!(
+            Day03.part_2(&area)?,
+            [(1, 1), (3, 1), (5, 1), (7, 1), (1, 2)]
+                .iter()
+                .map(|&(right, down)| naive_count_trees(&rows, right, down))
+                .product::<u64>()
+        );
+    }
+}
@@ -1,12 +1,19 @@
-use std::io::{BufReader, Read};
-
-use crate::convert_path_buf;
+use crate::error::SpanError;
+use crate::input::Input;
+#[cfg(test)]
+use crate::read_input;
+use crate::solution::{Metadata, Solution};
 use crate::{Error, Result};
+#[cfg(test)]
+use proptest::{collection::vec, prelude::*};
 use regex::Regex;
 use std::collections::HashSet;
+#[cfg(test)]
 use std::iter::FromIterator;
+#[cfg(test)]
 use std::path::PathBuf;
 use std::str::FromStr;
+use tracing::debug;
 
 // Consider using a parser combinator instead of regexes here, like Nom: https://crates.io/crates/nom
 lazy_static! {
@@ -39,7 +46,35 @@ lazy_static! {
     static ref EYE_COLOR_PARSER: Regex = Regex::new(r"^(amb|blu|brn|gry|grn|hzl|oth)$").unwrap();
     static ref PASSPORT_ID_PARSER: Regex = Regex::new(r"^(\d{9})$").unwrap();
 }
+/// The keys of the fields that every passport needs.  `cid` is optional.
+const REQUIRED_KEYS: [&str; 7] = ["byr", "iyr", "eyr", "hgt", "hcl", "ecl", "pid"];
+
 impl Field {
+    /// The field's key, as written in the input.
+    fn key(&self) -> &'static str {
+        use Field::*;
+        match self {
+            BirthYear(_) => "byr",
+            IssueYear(_) => "iyr",
+            ExpirationYear(_) => "eyr",
+            Height(_) => "hgt",
+            HairColor(_) => "hcl",
+            EyeColor(_) => "ecl",
+            PassportId(_) => "pid",
+            CountryId(_) => "cid",
+        }
+    }
+
+    fn value(&self) -> String {
+        use Field::*;
+        match self {
+            BirthYear(year) | IssueYear(year) | ExpirationYear(year) => year.to_string(),
+            Height(value) | HairColor(value) | EyeColor(value) | PassportId(value)
+            | CountryId(value) => value.clone(),
+        }
+    }
+
+    /// Whether this field's value satisfies part 2's rules.
     fn is_valid_part_2(&self) -> bool {
         use Field::*;
         // TODO: we can model this better with a "ValidPassportInput" struct...
@@ -47,35 +82,37 @@ impl Field {
             BirthYear(1920..=2002) => true,
             IssueYear(2010..=2020) => true,
             ExpirationYear(2020..=2030) => true,
-            Height(field) => HEIGHT_PARSER.captures(&field).map_or(false, |caps| {
+            Height(field) => HEIGHT_PARSER.captures(field).is_some_and(|caps| {
                 match (caps["value"].parse::<u16>(), &caps["unit"]) {
-                    (Ok(value), "cm") => 150 <= value && value <= 193,
-                    (Ok(value), "in") => 59 <= value && value <= 76,
+                    (Ok(value), "cm") => (150..=193).contains(&value),
+                    (Ok(value), "in") => (59..=76).contains(&value),
                     _ => false,
                 }
             }),
             HairColor(field) => HAIR_COLOR_PARSER
-                .captures(&field)
-                .map_or(false, |caps| caps[0].parse::<String>().is_ok()),
+                .captures(field)
+                .is_some_and(|caps| caps[0].parse::<String>().is_ok()),
             EyeColor(field) => EYE_COLOR_PARSER
-                .captures(&field)
-                .map_or(false, |caps| caps[0].parse::<String>().is_ok()),
+                .captures(field)
+                .is_some_and(|caps| caps[0].parse::<String>().is_ok()),
             PassportId(field) => PASSPORT_ID_PARSER
-                .captures(&field)
-                .map_or(false, |caps| caps[0].parse::<String>().is_ok()),
+                .captures(field)
+                .is_some_and(|caps| caps[0].parse::<String>().is_ok()),
             CountryId(_) => true,
             _ => false,
         }
     }
 }
 
+/// A single passport's fields, parsed from `key:value` pairs separated by whitespace.
 #[derive(Debug, Eq, PartialEq)]
-struct PassportInput {
+pub struct PassportInput {
     fields: HashSet<Field>,
 }
 
 impl PassportInput {
-    fn is_valid(&self) -> bool {
+    /// Whether every required field is present, as in part 1.  Only `cid` is optional.
+    pub fn is_valid_part_1(&self) -> bool {
         match self.fields.len() {
             // All 8 fields are present:
             8 => true,
@@ -83,17 +120,40 @@ impl PassportInput {
             7 => self
                 .fields
                 .iter()
-                .find(|field| match field {
-                    Field::CountryId(_) => true,
-                    _ => false,
-                })
+                .find(|field| matches!(field, Field::CountryId(_)))
                 .is_none(),
             _ => false,
         }
     }
 
-    fn is_valid_part_2(&self) -> bool {
-        self.is_valid() && self.fields.iter().all(|field| field.is_valid_part_2())
+    /// Whether every required field is present and has a valid value, as in part 2.
+    pub fn is_valid_part_2(&self) -> bool {
+        self.is_valid_part_1() && self.fields.iter().all(|field| field.is_valid_part_2())
+    }
+
+    /// Why the passport is invalid in part 2: each missing field, then each invalid value, eg:
+    /// "missing hgt" or "invalid byr: `1900`".  Empty when the passport is valid.
+    pub fn problems(&self) -> Vec<String> {
+        let mut invalid = self
+            .fields
+            .iter()
+            .filter(|field| !field.is_valid_part_2())
+            .map(|field| format!("invalid {}: `{}`", field.key(), field.value()))
+            .collect::<Vec<String>>();
+        // The fields are unordered:
+        invalid.sort();
+        self.missing_keys()
+            .map(|key| format!("missing {}", key))
+            .chain(invalid)
+            .collect()
+    }
+
+    /// The keys of the required fields that aren't present.
+    fn missing_keys(&self) -> impl Iterator<Item = &'static str> + '_ {
+        REQUIRED_KEYS
+            .iter()
+            .copied()
+            .filter(move |key| self.fields.iter().all(|field| field.key() != *key))
     }
 }
 
@@ -104,107 +164,116 @@ impl FromStr for PassportInput {
         let mut fields = HashSet::new();
         use Field::*;
 
-        // TODO: DRY this up
-        if let Some(caps) = BYR.captures(s) {
-            if BYR.find_iter(s).count() > 1 {
-                return Err(Error::InvalidState(format!(
-                    "too many matches of regex BYR"
-                )));
-            }
-            fields.insert(BirthYear(caps[1].parse()?));
+        if let Some(value) = parse_field(s, &BYR, "BYR")? {
+            fields.insert(BirthYear(value));
         }
-
-        if let Some(caps) = IYR.captures(s) {
-            if IYR.find_iter(s).count() > 1 {
-                return Err(Error::InvalidState(format!(
-                    "too many matches of regex IYR"
-                )));
-            }
-            fields.insert(IssueYear(caps[1].parse()?));
+        if let Some(value) = parse_field(s, &IYR, "IYR")? {
+            fields.insert(IssueYear(value));
         }
-
-        if let Some(caps) = EYR.captures(s) {
-            if EYR.find_iter(s).count() > 1 {
-                return Err(Error::InvalidState(format!(
-                    "too many matches of regex EYR"
-                )));
-            }
-            fields.insert(ExpirationYear(caps[1].parse()?));
+        if let Some(value) = parse_field(s, &EYR, "EYR")? {
+            fields.insert(ExpirationYear(value));
         }
-
-        if let Some(caps) = HGT.captures(s) {
-            if HGT.find_iter(s).count() > 1 {
-                return Err(Error::InvalidState(format!(
-                    "too many matches of regex HGT"
-                )));
-            }
-            fields.insert(Height(caps[1].parse()?));
+        if let Some(value) = parse_field(s, &HGT, "HGT")? {
+            fields.insert(Height(value));
         }
-
-        if let Some(caps) = HCL.captures(s) {
-            if HCL.find_iter(s).count() > 1 {
-                return Err(Error::InvalidState(format!(
-                    "too many matches of regex HCL"
-                )));
-            }
-            fields.insert(HairColor(caps[1].parse()?));
+        if let Some(value) = parse_field(s, &HCL, "HCL")? {
+            fields.insert(HairColor(value));
         }
-
-        if let Some(caps) = ECL.captures(s) {
-            if ECL.find_iter(s).count() > 1 {
-                return Err(Error::InvalidState(format!(
-                    "too many matches of regex ECL"
-                )));
-            }
-            fields.insert(EyeColor(caps[1].parse()?));
+        if let Some(value) = parse_field(s, &ECL, "ECL")? {
+            fields.insert(EyeColor(value));
         }
-
-        if let Some(caps) = PID.captures(s) {
-            if PID.find_iter(s).count() > 1 {
-                return Err(Error::InvalidState(format!(
-                    "too many matches of regex PID"
-                )));
-            }
-            fields.insert(PassportId(caps[1].parse()?));
+        if let Some(value) = parse_field(s, &PID, "PID")? {
+            fields.insert(PassportId(value));
         }
-
-        if let Some(caps) = CID.captures(s) {
-            if CID.find_iter(s).count() > 1 {
-                return Err(Error::InvalidState(format!(
-                    "too many matches of regex CID"
-                )));
-            }
-            fields.insert(CountryId(caps[1].parse()?));
+        if let Some(value) = parse_field(s, &CID, "CID")? {
+            fields.insert(CountryId(value));
         }
         Ok(Self { fields })
     }
 }
 
-fn get_passports_from_buffer(
-    mut buf_reader: BufReader<Box<dyn Read + '_>>,
-) -> Result<Vec<PassportInput>> {
-    let mut input = String::new();
-    buf_reader.read_to_string(&mut input)?;
-    input
-        .split("\n\n")
-        .map(|passport_string| passport_string.parse::<PassportInput>())
-        .collect::<Result<Vec<PassportInput>>>()
+/// Parses the value captured by the field's regex, if the field is present.  Each field may appear
+/// at most once per passport.
+fn parse_field<T>(s: &str, re: &Regex, name: &str) -> Result<Option<T>>
+where
+    T: FromStr,
+    Error: From<T::Err>,
+{
+    let mut matches = re.captures_iter(s);
+    let caps = match matches.next() {
+        Some(caps) => caps,
+        None => return Ok(None),
+    };
+    if let Some(duplicate) = matches.next() {
+        return Err(SpanError::at(
+            format!("too many matches of regex {}", name),
+            s,
+            duplicate.get(0).map_or(s, |m| m.as_str()),
+        )
+        .into());
+    }
+    Ok(Some(SpanError::parse_within(
+        s,
+        caps.get(1).map_or(s, |m| m.as_str()),
+    )?))
 }
 
-pub fn part_1(mut buf_reader: BufReader<Box<dyn Read + '_>>) -> Result<usize> {
-    let passports = get_passports_from_buffer(buf_reader)?;
-    Ok(passports
-        .iter()
-        .filter(|passport| passport.is_valid())
-        .count())
+fn get_passports(input: &Input) -> Result<Vec<PassportInput>> {
+    input.parse_paragraphs::<PassportInput>()
 }
 
-pub fn part_2(mut buf_reader: BufReader<Box<dyn Read + '_>>) -> Result<usize> {
-    let passports = get_passports_from_buffer(buf_reader)?;
-    Ok(passports
-        .iter()
-        .filter(|passport| passport.is_valid_part_2())
-        .count())
+#[derive(Debug)]
+pub struct Day04;
+
+impl Solution for Day04 {
+    type Model = Vec<PassportInput>;
+    type Part1 = usize;
+    type Part2 = usize;
+
+    fn metadata(&self) -> Metadata {
+        Metadata {
+            year: 2020,
+            day: 4,
+            title: "Passport Processing",
+        }
+    }
+
+    fn parse(&self, input: &Input) -> Result<Vec<PassportInput>> {
+        get_passports(input)
+    }
+
+    fn part_1(&self, passports: &Vec<PassportInput>) -> Result<usize> {
+        Ok(passports
+            .iter()
+            .filter(|passport| {
+                let valid = passport.is_valid_part_1();
+                if !valid {
+                    let missing = passport.missing_keys().collect::<Vec<&str>>();
+                    debug!(?missing, "rejected: missing fields");
+                }
+                valid
+            })
+            .count())
+    }
+
+    fn part_2(&self, passports: &Vec<PassportInput>) -> Result<usize> {
+        Ok(passports
+            .iter()
+            .filter(|passport| {
+                if !passport.is_valid_part_1() {
+                    let missing = passport.missing_keys().collect::<Vec<&str>>();
+                    debug!(?missing, "rejected: missing fields");
+                } else if let Some(field) = passport
+                    .fields
+                    .iter()
+                    .find(|field| !field.is_valid_part_2())
+                {
+                    debug!(?field, "rejected: invalid field");
+                }
+                passport.is_valid_part_2()
+            })
+            .count())
+    }
 }
 
 #[test]
@@ -214,33 +283,53 @@ fn test_from_string() -> Result<()> {
     \n eyr:2023 pid:028048884"
         .parse::<PassportInput>()?;
 
-    let fields = HashSet::from_iter(
-        vec![
-            IssueYear(2013),
-            EyeColor("amb".into()),
-            CountryId("350".into()),
-            ExpirationYear(2023),
-            PassportId("028048884".into()),
-        ]
-        .into_iter(),
+    let fields = HashSet::from_iter(vec![
+        IssueYear(2013),
+        EyeColor("amb".into()),
+        CountryId("350".into()),
+        ExpirationYear(2023),
+        PassportId("028048884".into()),
+    ]);
+    assert_eq!(passport, PassportInput { fields });
+    Ok(())
+}
+
+#[test]
+fn test_problems() -> Result<()> {
+    let passport = "byr:1900 iyr:2015 hgt:190in hcl:#123abc ecl:brn pid:000000001 cid:7"
+        .parse::<PassportInput>()?;
+    assert!(!passport.is_valid_part_1());
+    assert_eq!(
+        passport.problems(),
+        vec!["missing eyr", "invalid byr: `1900`", "invalid hgt: `190in`"]
     );
-    assert_eq!(passport, PassportInput { fields: fields });
+
+    let passport = "byr:1980 iyr:2015 eyr:2025 hgt:170cm hcl:#123abc ecl:brn pid:000000001"
+        .parse::<PassportInput>()?;
+    assert!(passport.is_valid_part_2());
+    assert!(passport.problems().is_empty());
     Ok(())
 }
 
 #[test]
 fn test_from_string_err_duplicates() -> Result<()> {
-    use Field::*;
     let passport =
         "iyr:2013 ecl:amb cid:350 eyr:2103 pid:028048884 eyr:2023".parse::<PassportInput>();
-    assert_eq!(passport.is_err(), true);
+    assert!(passport.is_err());
+
+    // The error points at the duplicate:
+    let span = passport
+        .unwrap_err()
+        .span()
+        .map(|span| span.columns.clone());
+    assert_eq!(span, Some(48..56));
     Ok(())
 }
 
 #[test]
-fn test_get_passports_from_buffer() -> Result<()> {
-    let p = Some(PathBuf::from("./src/exercises/day_04/test.txt"));
-    let passports = get_passports_from_buffer(convert_path_buf(p)?)?;
+fn test_get_passports() -> Result<()> {
+    let p = Some(PathBuf::from("./inputs/day_04.example.txt"));
+    let passports = get_passports(&read_input(p)?)?;
     assert_eq!(passports.len(), 4);
     Ok(())
 }
@@ -248,32 +337,32 @@ fn test_get_passports_from_buffer() -> Result<()> {
 #[test]
 fn test_height_field_validator() -> Result<()> {
     let field1 = Field::Height("150cm".into());
-    assert_eq!(field1.is_valid_part_2(), true);
+    assert!(field1.is_valid_part_2());
 
     let invalid_field = Field::Height("149cm".into());
-    assert_eq!(invalid_field.is_valid_part_2(), false);
+    assert!(!invalid_field.is_valid_part_2());
 
     let field2 = Field::Height("76in".into());
-    assert_eq!(field2.is_valid_part_2(), true);
+    assert!(field2.is_valid_part_2());
 
     let field3 = Field::Height("x76in".into());
-    assert_eq!(field3.is_valid_part_2(), false);
+    assert!(!field3.is_valid_part_2());
     Ok(())
 }
 
 #[test]
 fn test_hair_field_validator() -> Result<()> {
     let field1 = Field::HairColor("#60292f".into());
-    assert_eq!(field1.is_valid_part_2(), true);
+    assert!(field1.is_valid_part_2());
 
     let invalid_field = Field::HairColor("1f7352".into());
-    assert_eq!(invalid_field.is_valid_part_2(), false);
+    assert!(!invalid_field.is_valid_part_2());
 
     let field2 = Field::HairColor("#60292z".into()); // not a-f
-    assert_eq!(field2.is_valid_part_2(), false);
+    assert!(!field2.is_valid_part_2());
 
     let field3 = Field::HairColor("#60292f0".into()); // 7 digits
-    assert_eq!(field3.is_valid_part_2(), false);
+    assert!(!field3.is_valid_part_2());
 
     Ok(())
 }
@@ -281,22 +370,22 @@ fn test_hair_field_validator() -> Result<()> {
 #[test]
 fn test_eye_color_field_validator() -> Result<()> {
     let field = Field::EyeColor("amb".into());
-    assert_eq!(field.is_valid_part_2(), true);
+    assert!(field.is_valid_part_2());
 
     let field = Field::EyeColor("blu".into());
-    assert_eq!(field.is_valid_part_2(), true);
+    assert!(field.is_valid_part_2());
 
     let field = Field::EyeColor("oth".into());
-    assert_eq!(field.is_valid_part_2(), true);
+    assert!(field.is_valid_part_2());
 
     let field = Field::EyeColor("amb ".into());
-    assert_eq!(field.is_valid_part_2(), false);
+    assert!(!field.is_valid_part_2());
 
     let field = Field::EyeColor("ambx".into());
-    assert_eq!(field.is_valid_part_2(), false);
+    assert!(!field.is_valid_part_2());
 
     let field = Field::EyeColor("amb blu".into());
-    assert_eq!(field.is_valid_part_2(), false);
+    assert!(!field.is_valid_part_2());
 
     Ok(())
 }
@@ -304,24 +393,24 @@ fn test_eye_color_field_validator() -> Result<()> {
 #[test]
 fn test_passport_id_field_validator() -> Result<()> {
     let field = Field::PassportId("157096267".into());
-    assert_eq!(field.is_valid_part_2(), true);
+    assert!(field.is_valid_part_2());
 
     let field = Field::PassportId("000096267".into());
-    assert_eq!(field.is_valid_part_2(), true);
+    assert!(field.is_valid_part_2());
 
     let field = Field::PassportId(" 000096267".into());
-    assert_eq!(field.is_valid_part_2(), false);
+    assert!(!field.is_valid_part_2());
 
     let field = Field::PassportId("00096267".into());
-    assert_eq!(field.is_valid_part_2(), false);
+    assert!(!field.is_valid_part_2());
 
     Ok(())
 }
 
 #[test]
 fn test_part_1_example() -> Result<()> {
-    let p = Some(PathBuf::from("./src/exercises/day_04/test.txt"));
-    let res = part_1(convert_path_buf(p)?)?;
+    let p = Some(PathBuf::from("./inputs/day_04.example.txt"));
+    let res = Day04.part_1(&Day04.parse(&read_input(p)?)?)?;
 
     assert_eq!(res, 2);
     Ok(())
@@ -329,8 +418,8 @@ fn test_part_1_example() -> Result<()> {
 
 #[test]
 fn test_part_1() -> Result<()> {
-    let p = Some(PathBuf::from("./src/exercises/day_04/passports.txt"));
-    let res = part_1(convert_path_buf(p)?)?;
+    let p = Some(PathBuf::from("./inputs/day_04.txt"));
+    let res = Day04.part_1(&Day04.parse(&read_input(p)?)?)?;
 
     assert_eq!(res, 182);
     Ok(())
@@ -338,8 +427,8 @@ fn test_part_1() -> Result<()> {
 
 #[test]
 fn test_part_2_example() -> Result<()> {
-    let p = Some(PathBuf::from("./src/exercises/day_04/test_2.txt"));
-    let res = part_2(convert_path_buf(p)?)?;
+    let p = Some(PathBuf::from("./inputs/day_04.example_2.txt"));
+    let res = Day04.part_2(&Day04.parse(&read_input(p)?)?)?;
 
     assert_eq!(res, 4);
     Ok(())
@@ -347,9 +436,108 @@ fn test_part_2_example() -> Result<()> {
 
 #[test]
 fn test_part_2() -> Result<()> {
-    let p = Some(PathBuf::from("./src/exercises/day_04/passports.txt"));
-    let res = part_2(convert_path_buf(p)?)?;
+    let p = Some(PathBuf::from("./inputs/day_04.txt"));
+    let res = Day04.part_2(&Day04.parse(&read_input(p)?)?)?;
 
     assert_eq!(res, 109);
     Ok(())
 }
+
+#[cfg(test)]
+const FIELDS: [&str; 8] = ["byr", "iyr", "eyr", "hgt", "hcl", "ecl", "pid", "cid"];
+
+/// A value for the field, which may or may not satisfy part 2's rules.
+#[cfg(test)]
+fn arb_value(key: &'static str) -> BoxedStrategy<String> {
+    match key {
+        "byr" | "iyr" | "eyr" => (1900..2040_u16).prop_map(|year| year.to_string()).boxed(),
+        "hgt" => "[0-9]{2,3}(cm|in)?".boxed(),
+        "hcl" => "#?[0-9a-h]{5,7}".boxed(),
+        "ecl" => prop_oneof!["(amb|blu|brn|gry|grn|hzl|oth)", "[a-z]{3}"].boxed(),
+        "pid" => "[0-9]{8,10}".boxed(),
+        _ => "[0-9]{1,3}".boxed(),
+    }
+}
+
+/// A value for the field that satisfies part 2's rules.
+#[cfg(test)]
+fn arb_valid_value(key: &'static str) -> BoxedStrategy<String> {
+    match key {
+        "byr" => (1920..=2002_u16).prop_map(|year| year.to_string()).boxed(),
+        "iyr" => (2010..=2020_u16).prop_map(|year| year.to_string()).boxed(),
+        "eyr" => (2020..=2030_u16).prop_map(|year| year.to_string()).boxed(),
+        "hgt" => prop_oneof![
+            (150..=193_u16).prop_map(|cm| format!("{}cm", cm)),
+            (59..=76_u16).prop_map(|inches| format!("{}in", inches)),
+        ]
+        .boxed(),
+        "hcl" => "#[0-9a-f]{6}".boxed(),
+        "ecl" => "(amb|blu|brn|gry|grn|hzl|oth)".boxed(),
+        "pid" => "[0-9]{9}".boxed(),
+        _ => "[0-9]{1,3}".boxed(),
+    }
+}
+
+/// Random passports, as their `key:value` pairs in a random order.  Each field is present only if
+/// `present` generates `true` for it.
+#[cfg(test)]
+fn arb_passport(
+    value: fn(&'static str) -> BoxedStrategy<String>,
+    present: fn(&'static str) -> BoxedStrategy<bool>,
+) -> impl Strategy<Value = Vec<(&'static str, String)>> {
+    FIELDS
+        .iter()
+        .map(|&key| (present(key), value(key).prop_map(move |value| (key, value))))
+        .collect::<Vec<_>>()
+        .prop_map(|fields| {
+            fields
+                .into_iter()
+                .filter(|(present, _)| *present)
+                .map(|(_, field)| field)
+                .collect::<Vec<_>>()
+        })
+        .prop_shuffle()
+}
+
+/// Joins the fields with a random mix of spaces and newlines.
+#[cfg(test)]
+fn arb_passport_text(
+    passport: impl Strategy<Value = Vec<(&'static str, String)>>,
+) -> impl Strategy<Value = (Vec<(&'static str, String)>, String)> {
+    (passport, vec(prop_oneof![Just(" "), Just("\n")], 8)).prop_map(|(fields, separators)| {
+        let text = fields
+            .iter()
+            .zip(separators)
+            .map(|((key, value), separator)| format!("{}:{}{}", key, value, separator))
+            .collect::<String>();
+        (fields, text)
+    })
+}
+
+#[cfg(test)]
+proptest! {
+    #[test]
+    fn prop_is_valid_iff_required_fields_present(
+        (fields, text) in arb_passport_text(arb_passport(arb_value, |_| any::<bool>().boxed()))
+    ) {
+        let passport = text.parse::<PassportInput>()?;
+        let required_present = FIELDS
+            .iter()
+            .filter(|&&key| key != "cid")
+            .all(|&key| fields.iter().any(|(field, _)| *field == key));
+        prop_assert_eq!(passport.is_valid_part_1(), required_present);
+        // Part 2's rules are stricter than part 1's:
+        prop_assert!(!passport.is_valid_part_2() || passport.is_valid_part_1());
+        prop_assert_eq!(passport.problems().is_empty(), passport.is_valid_part_2());
+    }
+
+    #[test]
+    fn prop_valid_values_pass_part_2(
+        (_, text) in arb_passport_text(arb_passport(arb_valid_value, |key| match key {
+            "cid" => any::<bool>().boxed(),
+            _ => Just(true).boxed(),
+        }))
+    ) {
+        prop_assert!(text.parse::<PassportInput>()?.is_valid_part_2());
+    }
+}
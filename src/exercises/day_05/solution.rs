@@ -1,12 +1,17 @@
-use std::io::{BufRead, BufReader, Read};
-
-use crate::convert_path_buf;
+use crate::error::SpanError;
+use crate::input::Input;
+#[cfg(test)]
+use crate::read_input;
+use crate::solution::{Metadata, Solution};
 use crate::{Error, Result};
-use std::boxed::Box;
+#[cfg(test)]
+use proptest::prelude::*;
+#[cfg(test)]
 use std::path::PathBuf;
 
 use crate::vec_ext::VecExt;
 use std::convert::TryFrom;
+use std::ops::Range;
 use std::str::FromStr;
 
 trait BinaryEnum: TryFrom<u8, Error = Error> {
@@ -14,6 +19,7 @@ trait BinaryEnum: TryFrom<u8, Error = Error> {
     fn get_bit(&self) -> u8;
 }
 
+#[derive(Debug)]
 enum RowDirection {
     Front,
     Back,
@@ -43,6 +49,7 @@ impl TryFrom<u8> for RowDirection {
     }
 }
 
+#[derive(Debug)]
 enum SeatDirection {
     Left,
     Right,
@@ -71,21 +78,35 @@ impl TryFrom<u8> for SeatDirection {
     }
 }
 
+#[derive(Debug)]
 struct Instructions<T: BinaryEnum>(Vec<T>);
 
 impl<T: BinaryEnum> FromStr for Instructions<T> {
     type Err = Error;
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let mut bytes = s.as_bytes().iter().cloned().collect::<Vec<u8>>();
-        bytes.reverse(); // reversing because we want the LSB at the end.
-        let enums = bytes
-            .into_iter()
-            .map(|byte| T::try_from(byte))
-            .collect::<Result<Vec<T>>>()?;
-        Ok(Instructions(enums))
+        Self::from_span(s, 0..s.len())
     }
 }
+
 impl<T: BinaryEnum> Instructions<T> {
+    // Parses the instructions from the span of bytes within `s`, so that errors can point at
+    // their position in the whole seat assignment.
+    fn from_span(s: &str, span: Range<usize>) -> Result<Self> {
+        let enums = span
+            .rev() // reversing because we want the LSB at the end.
+            .map(|i| {
+                T::try_from(s.as_bytes()[i]).map_err(|err| {
+                    SpanError {
+                        cause: Some(Box::new(err)),
+                        ..SpanError::new("invalid seat instruction", s, i..i + 1)
+                    }
+                    .into()
+                })
+            })
+            .collect::<Result<Vec<T>>>()?;
+        Ok(Instructions(enums))
+    }
+
     fn get_value(&self) -> u32 {
         let base: u32 = 2;
         self.0
@@ -96,7 +117,10 @@ impl<T: BinaryEnum> Instructions<T> {
     }
 }
 
-struct SeatAssignment {
+/// A boarding pass, with 7 `F`/`B` characters to pick the row, then 3 `L`/`R` characters to pick
+/// the column (eg: `FBFBBFFRLR`).
+#[derive(Debug)]
+pub struct SeatAssignment {
     row_instructions: Instructions<RowDirection>,
     seat_instructions: Instructions<SeatDirection>,
 }
@@ -105,67 +129,97 @@ impl FromStr for SeatAssignment {
     type Err = Error;
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         if s.len() != 10 {
-            return Err(Error::InvalidInput(format!(
-                "SeatAssignment must be created from a str of length 10, not {}",
-                s.len()
-            )));
+            return Err(SpanError::at(
+                format!(
+                    "SeatAssignment must be created from a str of length 10, not {}",
+                    s.len()
+                ),
+                s,
+                s,
+            )
+            .into());
         }
-        let (rows, seats) = s.split_at(7);
         Ok(Self {
-            row_instructions: rows.parse::<Instructions<RowDirection>>()?,
-            seat_instructions: seats.parse::<Instructions<SeatDirection>>()?,
+            row_instructions: Instructions::from_span(s, 0..7)?,
+            seat_instructions: Instructions::from_span(s, 7..10)?,
         })
     }
 }
 
 impl SeatAssignment {
-    fn get_seat_id(&self) -> u32 {
-        self.row_instructions.get_value() * 8 + self.seat_instructions.get_value()
-    }
-}
-
-pub fn part_1(buf_reader: BufReader<Box<dyn Read + '_>>) -> Result<u32> {
-    buf_reader
-        .lines()
-        .into_iter()
-        .map(|line| Ok(line?.parse::<SeatAssignment>()?.get_seat_id()))
-        .collect::<Result<Vec<u32>>>()?
-        .into_iter()
-        .max()
-        .ok_or(Error::InvalidState(
-            "no valid seat id's can be derived from input".into(),
-        ))
-}
-
-pub fn part_2(buf_reader: BufReader<Box<dyn Read + '_>>) -> Result<u32> {
-    let seat_ids = buf_reader
-        .lines()
-        .into_iter()
-        .map(|line| Ok(line?.parse::<SeatAssignment>()?.get_seat_id()))
-        .collect::<Result<Vec<u32>>>()?
-        .sorted();
-
-    // NOTE: we can get better perf by using a binary search.
-    let (min_seat_index, min_seat_id) = seat_ids
-        .iter()
-        .enumerate()
-        .min()
-        .ok_or(Error::InvalidState("there must be a minimum.".into()))?;
-
-    seat_ids
-        .iter()
-        .enumerate()
-        .find_map(|(seat_index, &seat_id)| {
-            match (seat_id - min_seat_id) - ((seat_index - min_seat_index) as u32) {
-                0 => None,
-                1 => Some(Ok(seat_id - 1)),
-                _ => Some(Err(Error::InvalidState(format!(
-                    "this seat is out of range! index: {}, seat_id: {}",
-                    seat_index, seat_id
-                )))),
-            }
-        })
-        .ok_or(Error::InvalidState("Couldn't find our seat!".into()))?
+    pub fn row(&self) -> u32 {
+        self.row_instructions.get_value()
+    }
+
+    pub fn column(&self) -> u32 {
+        self.seat_instructions.get_value()
+    }
+
+    pub fn get_seat_id(&self) -> u32 {
+        self.row() * 8 + self.column()
+    }
+}
+
+#[derive(Debug)]
+pub struct Day05;
+
+impl Solution for Day05 {
+    type Model = Vec<SeatAssignment>;
+    type Part1 = u32;
+    type Part2 = u32;
+
+    fn metadata(&self) -> Metadata {
+        Metadata {
+            year: 2020,
+            day: 5,
+            title: "Binary Boarding",
+        }
+    }
+
+    fn parse(&self, input: &Input) -> Result<Vec<SeatAssignment>> {
+        input.parse_lines::<SeatAssignment>()
+    }
+
+    fn part_1(&self, assignments: &Vec<SeatAssignment>) -> Result<u32> {
+        assignments
+            .iter()
+            .map(|assignment| assignment.get_seat_id())
+            .max()
+            .ok_or_else(|| {
+                Error::InvalidInput("no valid seat id's can be derived from input".into())
+            })
+    }
+
+    fn part_2(&self, assignments: &Vec<SeatAssignment>) -> Result<u32> {
+        let seat_ids = assignments
+            .iter()
+            .map(|assignment| assignment.get_seat_id())
+            .collect::<Vec<u32>>()
+            .sorted();
+
+        // NOTE: we can get better perf by using a binary search.
+        let (min_seat_index, min_seat_id) = seat_ids
+            .iter()
+            .enumerate()
+            .min()
+            .ok_or_else(|| Error::InvalidInput("there must be a minimum.".into()))?;
+
+        seat_ids
+            .iter()
+            .enumerate()
+            .find_map(|(seat_index, &seat_id)| {
+                // Signed, since duplicate seat ids put a seat behind its index:
+                match i64::from(seat_id - min_seat_id) - (seat_index - min_seat_index) as i64 {
+                    0 => None,
+                    1 => Some(Ok(seat_id - 1)),
+                    _ => Some(Err(Error::InvalidInput(format!(
+                        "this seat is out of range! index: {}, seat_id: {}",
+                        seat_index, seat_id
+                    )))),
+                }
+            })
+            .ok_or_else(|| Error::InvalidInput("Couldn't find our seat!".into()))?
+    }
 }
 
 #[test]
@@ -188,6 +242,19 @@ fn parse_row_direction() -> Result<()> {
     Ok(())
 }
 
+#[test]
+fn parse_seat_assignment_errors() -> Result<()> {
+    let span = |s: &str| {
+        let err = s.parse::<SeatAssignment>().err()?;
+        err.span().map(|span| span.columns.clone())
+    };
+    assert_eq!(span("FBFBBFFRLR"), None);
+    assert_eq!(span("FBFBBXFRLR"), Some(5..6));
+    assert_eq!(span("FBFBBFFRLF"), Some(9..10));
+    assert_eq!(span("FBFBBFFRL"), Some(0..9));
+    Ok(())
+}
+
 #[test]
 fn parse_seat_direction() -> Result<()> {
     let instructions = "RLR".parse::<Instructions<SeatDirection>>()?;
@@ -215,6 +282,7 @@ fn value_of_instructions() -> Result<()> {
 #[test]
 fn value_of_seat_assignment() -> Result<()> {
     let assignment = "FBFBBFFRLR".parse::<SeatAssignment>()?;
+    assert_eq!((assignment.row(), assignment.column()), (44, 5));
     assert_eq!(assignment.get_seat_id(), 357);
 
     let assignment = "BFFFBBFRRR".parse::<SeatAssignment>()?;
@@ -225,10 +293,30 @@ fn value_of_seat_assignment() -> Result<()> {
     Ok(())
 }
 
+#[test]
+fn test_part_2_duplicate_seats() -> Result<()> {
+    let assignments = Day05.parse(&Input::from("FFFFFFFLLR\nFFFFFFFLLR\n"))?;
+    assert!(matches!(
+        Day05.part_2(&assignments),
+        Err(Error::InvalidInput(_))
+    ));
+    Ok(())
+}
+
+#[test]
+fn test_part_2_example() -> Result<()> {
+    // The example's seats aren't a full plane, so our seat can't be found in it:
+    let p = Some(PathBuf::from("./inputs/day_05.example.txt"));
+    let res = Day05.part_2(&Day05.parse(&read_input(p)?)?);
+
+    assert!(matches!(res, Err(Error::InvalidInput(_))));
+    Ok(())
+}
+
 #[test]
 fn test_part_1_example() -> Result<()> {
-    let p = Some(PathBuf::from("./src/exercises/day_05/test.txt"));
-    let res = part_1(convert_path_buf(p)?)?;
+    let p = Some(PathBuf::from("./inputs/day_05.example.txt"));
+    let res = Day05.part_1(&Day05.parse(&read_input(p)?)?)?;
 
     assert_eq!(res, 820);
     Ok(())
@@ -236,17 +324,68 @@ fn test_part_1_example() -> Result<()> {
 
 #[test]
 fn test_part_1() -> Result<()> {
-    let p = Some(PathBuf::from("./src/exercises/day_05/seat_assignments.txt"));
-    let res = part_1(convert_path_buf(p)?)?;
+    let p = Some(PathBuf::from("./inputs/day_05.txt"));
+    let res = Day05.part_1(&Day05.parse(&read_input(p)?)?)?;
 
     assert_eq!(res, 892);
     Ok(())
 }
 #[test]
 fn test_part_2() -> Result<()> {
-    let p = Some(PathBuf::from("./src/exercises/day_05/seat_assignments.txt"));
-    let res = part_2(convert_path_buf(p)?)?;
+    let p = Some(PathBuf::from("./inputs/day_05.txt"));
+    let res = Day05.part_2(&Day05.parse(&read_input(p)?)?)?;
 
     assert_eq!(res, 625);
     Ok(())
 }
+
+/// Encodes a seat id as a boarding pass, the inverse of `SeatAssignment::get_seat_id`.
+#[cfg(test)]
+fn encode(seat_id: u32) -> String {
+    (0..10)
+        .rev()
+        .map(|bit| {
+            let set = seat_id & (1 << bit) != 0;
+            match (bit >= 3, set) {
+                (true, false) => 'F',
+                (true, true) => 'B',
+                (false, false) => 'L',
+                (false, true) => 'R',
+            }
+        })
+        .collect()
+}
+
+/// A contiguous run of seat ids with a single empty seat inside it, in a random order, along with
+/// the empty seat.
+#[cfg(test)]
+fn arb_seats_with_gap() -> impl Strategy<Value = (Vec<u32>, u32)> {
+    (0..1000_u32, 3..24_u32)
+        .prop_flat_map(|(first, len)| (Just(first), Just(len), 1..len - 1))
+        .prop_flat_map(|(first, len, gap)| {
+            let seat_ids = (first..first + len)
+                .filter(|&seat_id| seat_id != first + gap)
+                .collect::<Vec<u32>>();
+            (Just(seat_ids).prop_shuffle(), Just(first + gap))
+        })
+}
+
+#[cfg(test)]
+proptest! {
+    #[test]
+    fn prop_seat_id_round_trips(row in 0..128_u32, column in 0..8_u32) {
+        let assignment = encode(row * 8 + column).parse::<SeatAssignment>()?;
+        prop_assert_eq!((assignment.row(), assignment.column()), (row, column));
+        prop_assert_eq!(assignment.get_seat_id(), row * 8 + column);
+    }
+
+    #[test]
+    fn prop_part_2_finds_the_gap((seat_ids, gap) in arb_seats_with_gap()) {
+        let input = Input::new(
+            seat_ids.iter().map(|&seat_id| encode(seat_id) + "\n").collect::<String>(),
+        );
+        let assignments = Day05.parse(&input)?;
+        prop_assert_eq!(Day05.part_1(&assignments)?, *seat_ids.iter().max().unwrap());
+        prop_assert_eq!(Day05.part_2(&assignments)?, gap);
+    }
+}
@@ -1,17 +1,19 @@
-use std::io::{BufRead, BufReader, Read};
-
-use crate::convert_path_buf;
+use crate::input::Input;
+#[cfg(test)]
+use crate::read_input;
+use crate::solution::{Metadata, Solution};
 use crate::{Error, Result};
-use std::boxed::Box;
+#[cfg(test)]
+use proptest::{collection::vec, prelude::*};
+#[cfg(test)]
 use std::path::PathBuf;
 
-use crate::vec_ext::VecExt;
-use std::collections::{HashMap, HashSet};
-use std::convert::TryFrom;
-use std::iter::FromIterator;
+use std::collections::HashSet;
 use std::str::FromStr;
 
-struct Group {
+/// A group's answers, with one line of questions answered "yes" per person.
+#[derive(Debug)]
+pub struct Group {
     any_yes_answers: HashSet<u8>,
     all_yes_answers: HashSet<u8>,
 }
@@ -21,7 +23,7 @@ impl FromStr for Group {
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         let any_yes_answers = s.lines().fold(HashSet::new(), |mut answers, line| {
-            answers.extend(line.as_bytes().into_iter().collect::<HashSet<&u8>>());
+            answers.extend(line.as_bytes().iter().collect::<HashSet<&u8>>());
             answers
         });
 
@@ -42,13 +44,16 @@ impl FromStr for Group {
 
         let all_yes_answers = s
             .lines()
-            .map(|line| line.as_bytes().into_iter().collect::<HashSet<&u8>>())
-            // .fold_first only work on Rust Nightly!
-            .fold_first(|answers, line| {
+            .map(|line| line.as_bytes().iter().collect::<HashSet<&u8>>())
+            // .fold_first was stabilized as .reduce:
+            .reduce(|answers, line| {
                 // Take the intersection between each person's answers:
                 &answers & &line
             })
-            .ok_or(Error::InvalidState("unable to fold_first".into()))?.into_iter().cloned().collect();
+            .ok_or_else(|| Error::InvalidInput("a group must have at least one answer".into()))?
+            .into_iter()
+            .cloned()
+            .collect();
 
         Ok(Self {
             any_yes_answers,
@@ -58,35 +63,49 @@ impl FromStr for Group {
 }
 
 impl Group {
-    fn get_any_yes_counts(&self) -> u32 {
-        self.any_yes_answers.len() as u32
+    /// The number of questions that anyone in the group answered "yes" to.
+    pub fn get_any_yes_counts(&self) -> usize {
+        self.any_yes_answers.len()
     }
-    fn get_all_yes_counts(&self) -> u32 {
-        self.all_yes_answers.len() as u32
+    /// The number of questions that everyone in the group answered "yes" to.
+    pub fn get_all_yes_counts(&self) -> usize {
+        self.all_yes_answers.len()
     }
 }
 
-pub fn part_1(mut buf_reader: BufReader<Box<dyn Read + '_>>) -> Result<u32> {
-    let mut input = String::new();
-    buf_reader.read_to_string(&mut input);
-    Ok(input
-        .split("\n\n")
-        .map(|group_str| group_str.parse::<Group>())
-        .collect::<Result<Vec<Group>>>()?
-        .into_iter()
-        .map(|group| group.get_any_yes_counts())
-        .sum::<u32>())
-}
-pub fn part_2(mut buf_reader: BufReader<Box<dyn Read + '_>>) -> Result<u32> {
-    let mut input = String::new();
-    buf_reader.read_to_string(&mut input);
-    Ok(input
-        .split("\n\n")
-        .map(|group_str| group_str.parse::<Group>())
-        .collect::<Result<Vec<Group>>>()?
-        .into_iter()
-        .map(|group| group.get_all_yes_counts())
-        .sum::<u32>())
+#[derive(Debug)]
+pub struct Day06;
+
+impl Solution for Day06 {
+    type Model = Vec<Group>;
+    type Part1 = usize;
+    type Part2 = usize;
+
+    fn metadata(&self) -> Metadata {
+        Metadata {
+            year: 2020,
+            day: 6,
+            title: "Custom Customs",
+        }
+    }
+
+    fn parse(&self, input: &Input) -> Result<Vec<Group>> {
+        input.parse_paragraphs::<Group>()
+    }
+
+    fn part_1(&self, groups: &Vec<Group>) -> Result<usize> {
+        Ok(groups
+            .iter()
+            .map(|group| group.get_any_yes_counts())
+            .sum::<usize>())
+    }
+
+    fn part_2(&self, groups: &Vec<Group>) -> Result<usize> {
+        Ok(groups
+            .iter()
+            .map(|group| group.get_all_yes_counts())
+            .sum::<usize>())
+    }
 }
 
 #[test]
@@ -113,8 +132,8 @@ fn make_group() -> Result<()> {
 
 #[test]
 fn part_1_example() -> Result<()> {
-    let p = Some(PathBuf::from("./src/exercises/day_06/test.txt"));
-    let res = part_1(convert_path_buf(p)?)?;
+    let p = Some(PathBuf::from("./inputs/day_06.example.txt"));
+    let res = Day06.part_1(&Day06.parse(&read_input(p)?)?)?;
 
     assert_eq!(res, 11);
     Ok(())
@@ -122,8 +141,8 @@ fn part_1_example() -> Result<()> {
 
 #[test]
 fn test_part_1() -> Result<()> {
-    let p = Some(PathBuf::from("./src/exercises/day_06/answers.txt"));
-    let res = part_1(convert_path_buf(p)?)?;
+    let p = Some(PathBuf::from("./inputs/day_06.txt"));
+    let res = Day06.part_1(&Day06.parse(&read_input(p)?)?)?;
 
     assert_eq!(res, 6565);
     Ok(())
@@ -131,17 +150,69 @@ fn test_part_1() -> Result<()> {
 
 #[test]
 fn part_2_example() -> Result<()> {
-    let p = Some(PathBuf::from("./src/exercises/day_06/test.txt"));
-    let res = part_2(convert_path_buf(p)?)?;
+    let p = Some(PathBuf::from("./inputs/day_06.example.txt"));
+    let res = Day06.part_2(&Day06.parse(&read_input(p)?)?)?;
 
     assert_eq!(res, 6);
     Ok(())
 }
 #[test]
 fn test_part_2() -> Result<()> {
-    let p = Some(PathBuf::from("./src/exercises/day_06/answers.txt"));
-    let res = part_2(convert_path_buf(p)?)?;
+    let p = Some(PathBuf::from("./inputs/day_06.txt"));
+    let res = Day06.part_2(&Day06.parse(&read_input(p)?)?)?;
 
     assert_eq!(res, 3137);
     Ok(())
 }
+
+/// Random groups, as each person's line of "yes" answers from a small alphabet.
+#[cfg(test)]
+fn arb_groups() -> impl Strategy<Value = Vec<Vec<String>>> {
+    vec(vec("[a-h]{1,8}", 1..6), 0..10)
+}
+
+#[cfg(test)]
+proptest! {
+    #[test]
+    fn prop_all_yes_within_any_yes(people in vec("[a-h]{1,8}", 1..6)) {
+        let group = people.join("\n").parse::<Group>()?;
+        prop_assert!(group.all_yes_answers.is_subset(&group.any_yes_answers));
+
+        let answers = |person: &String| person.bytes().collect::<HashSet<u8>>();
+        let any_yes = people.iter().flat_map(|person| person.bytes()).collect::<HashSet<u8>>();
+        let all_yes = any_yes
+            .iter()
+            .filter(|answer| people.iter().all(|person| answers(person).contains(answer)))
+            .count();
+        prop_assert_eq!(group.get_any_yes_counts(), any_yes.len());
+        prop_assert_eq!(group.get_all_yes_counts(), all_yes);
+    }
+
+    #[test]
+    fn prop_parts_sum_over_groups(groups in arb_groups()) {
+        let input = Input::new(
+            groups
+                .iter()
+                .map(|people| people.join("\n"))
+                .collect::<Vec<String>>()
+                .join("\n\n"),
+        );
+        let parsed = Day06.parse(&input)?;
+        prop_assert_eq!(parsed.len(), groups.len());
+        let part_1 = Day06.part_1(&parsed)?;
+        let part_2 = Day06.part_2(&parsed)?;
+        prop_assert!(part_2 <= part_1);
+        prop_assert_eq!(
+            part_1,
+            groups
+                .iter()
+                .map(|people| {
+                    people
+                        .join("\n")
+                        .parse::<Group>()
+                        .map(|group| group.get_any_yes_counts())
+                })
+                .sum::<Result<usize>>()?
+        );
+    }
+}
@@ -1,4 +1,5 @@
-pub use solution::part_1;
-pub use solution::part_2;
+#[cfg(any(test, feature = "fuzzing"))]
+pub(crate) use solution::Rule;
+pub use solution::{Bag, BagsMap, Day07};
 
 mod solution;
@@ -1,25 +1,44 @@
-use std::io::{BufRead, BufReader, Read};
-
-use crate::convert_path_buf;
+use crate::error::SpanError;
+use crate::input::Input;
+#[cfg(test)]
+use crate::read_input;
+use crate::solution::{Metadata, Solution};
 use crate::{Error, Result};
+#[cfg(test)]
+use proptest::{collection::vec, prelude::*};
 use regex::Regex;
-use std::boxed::Box;
+#[cfg(test)]
 use std::path::PathBuf;
 
-use crate::vec_ext::VecExt;
 use std::collections::{HashMap, HashSet};
-use std::convert::TryFrom;
 use std::iter::FromIterator;
-use std::ops::Deref;
 use std::str::FromStr;
+use tracing::debug;
 
+/// A bag, identified by its color (eg: "shiny gold").
 #[derive(Eq, PartialEq, Debug, Hash, Clone)]
-struct Bag(String); // represents the bags color
+pub struct Bag(String);
+
+impl Bag {
+    pub fn new(color: impl Into<String>) -> Self {
+        Self(color.into())
+    }
+
+    pub fn color(&self) -> &str {
+        &self.0
+    }
+}
+
+impl From<&str> for Bag {
+    fn from(color: &str) -> Self {
+        Self::new(color)
+    }
+}
 
 type BagCount = u8;
 
 #[derive(Eq, PartialEq, Debug, Hash, Clone)]
-struct Rule {
+pub(crate) struct Rule {
     container: Bag,
     items: Vec<(BagCount, Bag)>, // each tuple represents the number of bags that can fit inside the container.
 }
@@ -52,42 +71,42 @@ impl FromStr for Rule {
 
         let rule_parts = s.split("contain").collect::<Vec<&str>>();
         if rule_parts.len() != 2 {
-            return Err(Error::InvalidInput(format!(
-                "Invalid rule length: {}",
-                rule_parts.len()
-            )));
+            return Err(
+                SpanError::at(format!("Invalid rule length: {}", rule_parts.len()), s, s).into(),
+            );
         }
-        let container_input = rule_parts
-            .get(0)
-            .ok_or(Error::InvalidInput(format!(
-                "Invalid container input: {:?}",
-                rule_parts
-            )))?
-            .deref();
-        let container_caps = CONTAINER_RE
-            .captures(container_input)
-            .ok_or(Error::InvalidInput(format!(
-                "Invalid container capture on input: {}",
-                container_input
-            )))?;
-        let container = Bag(container_caps["color"].parse()?);
+        let container_input = *rule_parts.first().ok_or_else(|| {
+            SpanError::at(format!("Invalid container input: {:?}", rule_parts), s, s)
+        })?;
+        let container_caps = CONTAINER_RE.captures(container_input).ok_or_else(|| {
+            SpanError::at(
+                format!("Invalid container capture on input: {}", container_input),
+                s,
+                container_input,
+            )
+        })?;
+        let container = Bag(SpanError::parse_within(s, &container_caps["color"])?);
 
         let items_input = rule_parts
             .get(1)
-            .ok_or(Error::InvalidInput(format!("Invalid items input: {}", s)))?;
+            .ok_or_else(|| SpanError::at(format!("Invalid items input: {}", s), s, s))?;
         let items = match items_input.contains("no other bags") {
             // When the bag has no match (eg: "faded blue bags contain no other bags.")
             true => Vec::new(),
             false => items_input
                 .split(",")
                 .map(|item_str| {
-                    let caps = ITEM_RE
-                        .captures(item_str)
-                        .ok_or(Error::InvalidInput(format!(
-                            "Invalid item input: {}",
-                            item_str
-                        )))?;
-                    Ok((caps["count"].parse::<u8>()?, Bag(caps["color"].parse()?)))
+                    let caps = ITEM_RE.captures(item_str).ok_or_else(|| {
+                        SpanError::at(
+                            format!("Invalid item input: {}", item_str.trim()),
+                            s,
+                            item_str.trim(),
+                        )
+                    })?;
+                    Ok((
+                        SpanError::parse_within::<u8>(s, &caps["count"])?,
+                        Bag(SpanError::parse_within(s, &caps["color"])?),
+                    ))
                 })
                 .collect::<Result<Vec<(u8, Bag)>>>()?,
         };
@@ -96,9 +115,9 @@ impl FromStr for Rule {
     }
 }
 
-// mapping of bags to their valid containers.
+/// A mapping of bags to their valid containers, and to the bags that they contain.
 #[derive(Debug)]
-struct BagsMap {
+pub struct BagsMap {
     // maps a bag to the bags which contain it:
     items_to_containers: HashMap<Bag, HashSet<Bag>>,
     // maps a bag to the count and type of bag that it contains:
@@ -127,9 +146,7 @@ impl From<Vec<Rule>> for BagsMap {
             |mut containers_map, rule| {
                 let container = rule.container;
                 let items = containers_map.entry(container).or_default();
-                items.extend(HashSet::<(BagCount, Bag)>::from_iter(
-                    rule.items.into_iter(),
-                ));
+                items.extend(HashSet::<(BagCount, Bag)>::from_iter(rule.items));
                 containers_map
             },
         );
@@ -141,23 +158,42 @@ impl From<Vec<Rule>> for BagsMap {
     }
 }
 
+/// Parses a whole set of rules, one per line.
+impl FromStr for BagsMap {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(Self::from(Input::from(s).parse_lines::<Rule>()?))
+    }
+}
+
 impl BagsMap {
-    // Returns all bags that contain the provided bag, using BFT
-    fn count_containing_bags(&self, bag: Bag) -> Result<u32> {
-        let mut to_visit = HashSet::<&Bag>::from_iter(vec![&bag].into_iter());
+    /// Every bag that appears in the rules, either as a container or as an item.
+    pub fn bags(&self) -> impl Iterator<Item = &Bag> {
+        self.items_to_containers.keys()
+    }
+
+    /// Returns the number of bags that can eventually contain the provided bag, using BFT.
+    pub fn count_containing_bags(&self, bag: &Bag) -> Result<u32> {
+        let mut to_visit = HashSet::<&Bag>::from_iter(vec![bag]);
         let mut visited = HashSet::<&Bag>::new();
         loop {
             if to_visit.is_empty() {
-                visited.remove(&bag);
+                visited.remove(bag);
                 return Ok(visited.len() as u32);
             }
+            debug!(
+                frontier = to_visit.len(),
+                visited = visited.len(),
+                "expanding the BFS frontier"
+            );
 
             let to_visit_next = to_visit
                 .iter()
                 .map(|item_bag| {
                     self.items_to_containers
                         .get(item_bag)
-                        .ok_or(Error::InvalidState(format!(
+                        .ok_or(Error::InvalidInput(format!(
                             "Item bag is not found in our bag map! {:?}",
                             item_bag
                         )))
@@ -169,8 +205,7 @@ impl BagsMap {
                     set
                 })
                 .difference(&visited)
-                // TODO: avoid this double reference...
-                .map(|bag| *bag)
+                .copied()
                 .collect::<HashSet<&Bag>>();
 
             let old_visited = std::mem::replace(&mut to_visit, to_visit_next);
@@ -178,45 +213,98 @@ impl BagsMap {
         }
     }
 
-    // Returns total number bags that the provided bag contains, via DFT
+    /// Returns the total number of bags that the provided bag contains, via DFT.  A bag that
+    /// eventually contains itself would contain infinitely many bags, so it's an error.
     //
-    // NOTE: We can avoid blowing the stack by using tail recursion or a stack/iterative approach.
-    fn count_item_bags(&self, bag: &Bag) -> Result<u32> {
-        Ok(self
-            .containers_to_items
+    // The DFT keeps its own stack rather than recursing, so that deeply nested bags can't overflow
+    // the thread's stack.  Each bag's count is memoized, and is `None` while its items are still
+    // being counted, so that finding such a bag again means that it contains itself.
+    pub fn count_item_bags(&self, bag: &Bag) -> Result<u32> {
+        let items = |bag: &Bag| {
+            self.containers_to_items
+                .get(bag)
+                .ok_or(Error::InvalidInput(format!(
+                    "Bag has no rule for the bags it contains: {:?}",
+                    bag
+                )))
+        };
+        let mut counts = HashMap::<&Bag, Option<u32>>::new();
+        // Each bag is pushed once to visit it, and once more to sum its items after visiting them:
+        let mut to_visit = vec![(bag, false)];
+        while let Some((bag, items_counted)) = to_visit.pop() {
+            if items_counted {
+                let overflow =
+                    || Error::InvalidInput(format!("Bag contains too many bags: {:?}", bag));
+                let mut total = 0_u32;
+                for (count, item) in items(bag)? {
+                    // Each item bag counts itself, plus the bags inside it:
+                    let item_bags = counts
+                        .get(item)
+                        .copied()
+                        .flatten()
+                        .ok_or(Error::InvalidState(format!(
+                            "Item bag was not counted before its container: {:?}",
+                            item
+                        )))?
+                        .checked_add(1)
+                        .and_then(|item_bags| item_bags.checked_mul(u32::from(*count)))
+                        .ok_or_else(overflow)?;
+                    total = total.checked_add(item_bags).ok_or_else(overflow)?;
+                }
+                counts.insert(bag, Some(total));
+                continue;
+            }
+            match counts.get(bag) {
+                Some(Some(_)) => continue,
+                Some(None) => {
+                    return Err(Error::InvalidInput(format!(
+                        "Bag eventually contains itself: {:?}",
+                        bag
+                    )))
+                }
+                None => counts.insert(bag, None),
+            };
+            to_visit.push((bag, true));
+            to_visit.extend(items(bag)?.iter().map(|(_, item)| (item, false)));
+        }
+        counts
             .get(bag)
+            .copied()
+            .flatten()
             .ok_or(Error::InvalidState(format!(
-                "No bag container found in container_to_itmes: {:?}",
+                "Bag was never counted: {:?}",
                 bag
-            )))?
-            .iter()
-            .map(|(count, item)| {
-                Ok(u32::from(*count) + (u32::from(*count) * self.count_item_bags(item)?))
-            })
-            .collect::<Result<Vec<u32>>>()?
-            .into_iter()
-            .sum::<u32>())
+            )))
     }
 }
 
-pub fn part_1(mut buf_reader: BufReader<Box<dyn Read + '_>>) -> Result<u32> {
-    let map = BagsMap::from(
-        buf_reader
-            .lines()
-            .map(|line| line?.parse::<Rule>())
-            .collect::<Result<Vec<Rule>>>()?,
-    );
-    map.count_containing_bags(Bag("shiny gold".into()))
-}
+#[derive(Debug)]
+pub struct Day07;
+
+impl Solution for Day07 {
+    type Model = BagsMap;
+    type Part1 = u32;
+    type Part2 = u32;
+
+    fn metadata(&self) -> Metadata {
+        Metadata {
+            year: 2020,
+            day: 7,
+            title: "Handy Haversacks",
+        }
+    }
 
-pub fn part_2(mut buf_reader: BufReader<Box<dyn Read + '_>>) -> Result<u32> {
-    let map = BagsMap::from(
-        buf_reader
-            .lines()
-            .map(|line| line?.parse::<Rule>())
-            .collect::<Result<Vec<Rule>>>()?,
-    );
-    map.count_item_bags(&Bag("shiny gold".into()))
+    fn parse(&self, input: &Input) -> Result<BagsMap> {
+        Ok(BagsMap::from(input.parse_lines::<Rule>()?))
+    }
+
+    fn part_1(&self, map: &BagsMap) -> Result<u32> {
+        map.count_containing_bags(&Bag("shiny gold".into()))
+    }
+
+    fn part_2(&self, map: &BagsMap) -> Result<u32> {
+        map.count_item_bags(&Bag("shiny gold".into()))
+    }
 }
 
 #[test]
@@ -254,6 +342,23 @@ fn test_parse_rule() -> Result<()> {
     Ok(())
 }
 
+#[test]
+fn test_parse_rule_error_span() -> Result<()> {
+    let input = Input::from(
+        "bright white bags contain 1 shiny gold bag.\n\
+         dark orange bags contain 3 bright white bags, x muted yellow bags.\n",
+    );
+    let err = Day07.parse(&input).unwrap_err();
+    let span = err.span().expect("the error should carry a span");
+    assert_eq!(span.line, 2);
+    assert_eq!(span.columns, 46..66);
+    assert_eq!(
+        span.source_line,
+        "dark orange bags contain 3 bright white bags, x muted yellow bags."
+    );
+    Ok(())
+}
+
 #[test]
 fn test_parse_bag_containers_map() -> Result<()> {
     let map = BagsMap::from(vec![
@@ -301,10 +406,53 @@ fn test_parse_bag_items_map() -> Result<()> {
     Ok(())
 }
 
+#[test]
+fn test_count_item_bags_errors() -> Result<()> {
+    let map = "\
+        bright white bags contain 1 shiny gold bag.\n\
+        shiny gold bags contain 2 bright white bags.\n\
+    "
+    .parse::<BagsMap>()?;
+    assert!(map.count_item_bags(&Bag::from("shiny gold")).is_err());
+    assert_eq!(map.count_containing_bags(&Bag::from("shiny gold"))?, 1);
+
+    let map = "\
+        light red bags contain 255 bright white bags.\n\
+        bright white bags contain 255 shiny gold bags.\n\
+        shiny gold bags contain 255 muted yellow bags.\n\
+        muted yellow bags contain 255 faded blue bags.\n\
+        faded blue bags contain 255 dark orange bags.\n\
+        dark orange bags contain no other bags.\n\
+    "
+    .parse::<BagsMap>()?;
+    assert!(map.count_item_bags(&Bag::from("light red")).is_err());
+    assert_eq!(map.count_item_bags(&Bag::from("muted yellow"))?, 255 * 256);
+    Ok(())
+}
+
+#[test]
+fn test_count_item_bags_deep_chain() -> Result<()> {
+    // Deep enough to overflow the stack of a recursive count:
+    let depth = 50_000;
+    let map = BagsMap::from(
+        (0..depth)
+            .map(|i| Rule {
+                container: Bag::new(color(i)),
+                items: match i + 1 < depth {
+                    true => vec![(1, Bag::new(color(i + 1)))],
+                    false => vec![],
+                },
+            })
+            .collect::<Vec<Rule>>(),
+    );
+    assert_eq!(map.count_item_bags(&Bag::new(color(0)))?, depth as u32 - 1);
+    Ok(())
+}
+
 #[test]
 fn test_part_1_example() -> Result<()> {
-    let p = Some(PathBuf::from("./src/exercises/day_07/test.txt"));
-    let res = part_1(convert_path_buf(p)?)?;
+    let p = Some(PathBuf::from("./inputs/day_07.example.txt"));
+    let res = Day07.part_1(&Day07.parse(&read_input(p)?)?)?;
 
     assert_eq!(res, 4);
     Ok(())
@@ -312,8 +460,8 @@ fn test_part_1_example() -> Result<()> {
 
 #[test]
 fn test_part_1() -> Result<()> {
-    let p = Some(PathBuf::from("./src/exercises/day_07/rules.txt"));
-    let res = part_1(convert_path_buf(p)?)?;
+    let p = Some(PathBuf::from("./inputs/day_07.txt"));
+    let res = Day07.part_1(&Day07.parse(&read_input(p)?)?)?;
 
     assert_eq!(res, 257);
     Ok(())
@@ -321,8 +469,8 @@ fn test_part_1() -> Result<()> {
 
 #[test]
 fn test_part_2_example() -> Result<()> {
-    let p = Some(PathBuf::from("./src/exercises/day_07/test.txt"));
-    let res = part_2(convert_path_buf(p)?)?;
+    let p = Some(PathBuf::from("./inputs/day_07.example.txt"));
+    let res = Day07.part_2(&Day07.parse(&read_input(p)?)?)?;
 
     assert_eq!(res, 32);
     Ok(())
@@ -330,9 +478,91 @@ fn test_part_2_example() -> Result<()> {
 
 #[test]
 fn test_part_2_example_2() -> Result<()> {
-    let p = Some(PathBuf::from("./src/exercises/day_07/test_2.txt"));
-    let res = part_2(convert_path_buf(p)?)?;
+    let p = Some(PathBuf::from("./inputs/day_07.example_2.txt"));
+    let res = Day07.part_2(&Day07.parse(&read_input(p)?)?)?;
 
     assert_eq!(res, 126);
     Ok(())
 }
+
+/// Random acyclic rules for up to 8 bags, as the items of each bag.  Bags only contain bags with a
+/// higher index, so that there are no cycles.
+#[cfg(test)]
+fn arb_rules() -> impl Strategy<Value = Vec<Vec<(BagCount, usize)>>> {
+    (1..=8_usize).prop_flat_map(|n| {
+        (0..n)
+            .map(|i| {
+                vec(proptest::option::of(1..=4_u8), n - i - 1).prop_map(move |counts| {
+                    counts
+                        .into_iter()
+                        .enumerate()
+                        .filter_map(|(j, count)| count.map(|count| (count, i + j + 1)))
+                        .collect::<Vec<(BagCount, usize)>>()
+                })
+            })
+            .collect::<Vec<_>>()
+    })
+}
+
+#[cfg(test)]
+fn color(i: usize) -> String {
+    format!("shade{} hue{}", i, i)
+}
+
+#[cfg(test)]
+fn rules_to_input(rules: &[Vec<(BagCount, usize)>]) -> Input {
+    Input::new(
+        rules
+            .iter()
+            .enumerate()
+            .map(|(i, items)| {
+                let items = match items.len() {
+                    0 => "no other bags".to_string(),
+                    _ => items
+                        .iter()
+                        .map(|&(count, j)| {
+                            let plural = if count == 1 { "" } else { "s" };
+                            format!("{} {} bag{}", count, color(j), plural)
+                        })
+                        .collect::<Vec<String>>()
+                        .join(", "),
+                };
+                format!("{} bags contain {}.\n", color(i), items)
+            })
+            .collect::<String>(),
+    )
+}
+
+#[cfg(test)]
+fn naive_contains(rules: &[Vec<(BagCount, usize)>], container: usize, bag: usize) -> bool {
+    rules[container]
+        .iter()
+        .any(|&(_, item)| item == bag || naive_contains(rules, item, bag))
+}
+
+#[cfg(test)]
+fn naive_item_count(rules: &[Vec<(BagCount, usize)>], bag: usize) -> u32 {
+    rules[bag]
+        .iter()
+        .map(|&(count, item)| u32::from(count) * (1 + naive_item_count(rules, item)))
+        .sum()
+}
+
+#[cfg(test)]
+proptest! {
+    #[test]
+    fn prop_counts_match_naive_counts(rules in arb_rules()) {
+        let map = Day07.parse(&rules_to_input(&rules))?;
+        prop_assert_eq!(map.bags().count(), rules.len());
+        for bag in 0..rules.len() {
+            let containing = (0..rules.len())
+                .filter(|&container| naive_contains(&rules, container, bag))
+                .count() as u32;
+            prop_assert_eq!(map.count_containing_bags(&Bag::new(color(bag)))?, containing);
+            prop_assert_eq!(
+                map.count_item_bags(&Bag::new(color(bag)))?,
+                naive_item_count(&rules, bag)
+            );
+        }
+    }
+}
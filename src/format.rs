@@ -0,0 +1,176 @@
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+
+use crate::aoc_result::AocReturn;
+use crate::timing::Timing;
+use crate::{Error, Result};
+
+/// Machine-readable output formats, so that scripts don't need to scrape the cli output.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum Format {
+    /// Just the answer, one per line.
+    Plain,
+    Json,
+    Csv,
+}
+
+impl FromStr for Format {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "plain" => Ok(Self::Plain),
+            "json" => Ok(Self::Json),
+            "csv" => Ok(Self::Csv),
+            _ => Err(Error::InvalidInput(format!(
+                "Invalid format: {}, expected one of: plain, json, csv",
+                s
+            ))),
+        }
+    }
+}
+
+/// A single day's part, as it is written out in each format.
+#[derive(Debug, Serialize)]
+pub struct Report {
+    pub year: usize,
+    pub day: usize,
+    pub part: usize,
+    pub answer: Option<AocReturn>,
+    #[serde(rename = "type")]
+    pub type_name: Option<&'static str>,
+    pub timing: Option<Timing>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl Report {
+    pub fn new(
+        year: usize,
+        day: usize,
+        part: usize,
+        outcome: &Result<(AocReturn, Timing)>,
+    ) -> Self {
+        match outcome {
+            Ok((answer, timing)) => Self {
+                year,
+                day,
+                part,
+                answer: Some(answer.clone()),
+                type_name: Some(answer.type_name()),
+                timing: Some(*timing),
+                error: None,
+            },
+            Err(err) => Self {
+                year,
+                day,
+                part,
+                answer: None,
+                type_name: None,
+                timing: None,
+                error: Some(err.chain().to_string()),
+            },
+        }
+    }
+}
+
+/// Renders the reports in the given format.  A single report is rendered as a JSON object, and
+/// multiple reports as a JSON array.
+pub fn render(format: Format, reports: &[Report]) -> Result<String> {
+    Ok(match format {
+        Format::Plain => reports
+            .iter()
+            .map(|report| match (&report.answer, &report.error) {
+                (Some(answer), _) => format!("{}\n", answer),
+                (None, error) => format!("error: {}\n", error.as_deref().unwrap_or_default()),
+            })
+            .collect(),
+        Format::Json => match reports {
+            [report] => format!("{}\n", serde_json::to_string(report)?),
+            reports => format!("{}\n", serde_json::to_string(reports)?),
+        },
+        Format::Csv => {
+            std::iter::once("year,day,part,answer,type,parse_ns,solve_ns,error\n".into())
+                .chain(reports.iter().map(|report| {
+                    format!(
+                        "{},{},{},{},{},{},{},{}\n",
+                        report.year,
+                        report.day,
+                        report.part,
+                        csv_field(&report.answer.as_ref().map(ToString::to_string)),
+                        report.type_name.unwrap_or_default(),
+                        csv_field(&report.timing.map(|t| t.parse.as_nanos().to_string())),
+                        csv_field(&report.timing.map(|t| t.solve.as_nanos().to_string())),
+                        csv_field(&report.error),
+                    )
+                }))
+                .collect()
+        }
+    })
+}
+
+/// Quotes the field if it contains a delimiter, quote or newline.
+fn csv_field(field: &Option<String>) -> String {
+    match field {
+        Some(field) if field.contains(&[',', '"', '\n'][..]) => {
+            format!("\"{}\"", field.replace('"', "\"\""))
+        }
+        Some(field) => field.clone(),
+        None => String::new(),
+    }
+}
+
+#[cfg(test)]
+fn test_reports() -> Vec<Report> {
+    use std::time::Duration;
+    vec![
+        Report::new(
+            2020,
+            1,
+            1,
+            &Ok((
+                Some(876459_u32).into(),
+                Timing {
+                    parse: Duration::from_nanos(1500),
+                    solve: Duration::from_nanos(200),
+                },
+            )),
+        ),
+        Report::new(2020, 1, 2, &Ok((vec![1_u32, 2].into(), Timing::default()))),
+        Report::new(2019, 2, 1, &Err(Error::UnimplementedDay(2019, 2))),
+    ]
+}
+
+#[test]
+fn test_render_plain() -> Result<()> {
+    assert_eq!(
+        render(Format::Plain, &test_reports())?,
+        "876459\n1,2\nerror: Day `2` of 2019 has not been implemented yet\n"
+    );
+    Ok(())
+}
+
+#[test]
+fn test_render_json() -> Result<()> {
+    let reports = test_reports();
+    assert_eq!(
+        render(Format::Json, &reports[..1])?,
+        r#"{"year":2020,"day":1,"part":1,"answer":876459,"type":"Option<u32>","timing":{"parse_ns":1500,"solve_ns":200}}
+"#
+    );
+    assert!(render(Format::Json, &reports)?.starts_with("[{"));
+    Ok(())
+}
+
+#[test]
+fn test_render_csv() -> Result<()> {
+    assert_eq!(
+        render(Format::Csv, &test_reports())?,
+        "year,day,part,answer,type,parse_ns,solve_ns,error\n\
+         2020,1,1,876459,Option<u32>,1500,200,\n\
+         2020,1,2,\"1,2\",Vec<u32>,0,0,\n\
+         2019,2,1,,,,,Day `2` of 2019 has not been implemented yet\n"
+    );
+    Ok(())
+}
@@ -0,0 +1,193 @@
+use std::fmt;
+
+use crate::exercises::day_01::Day01;
+use crate::exercises::day_02::{Day02, PolicyWithPassword};
+use crate::exercises::day_03::{Area, Day03};
+use crate::exercises::day_04::{Day04, PassportInput};
+use crate::exercises::day_05::{Day05, SeatAssignment};
+use crate::exercises::day_06::{Day06, Group};
+use crate::exercises::day_07::{BagsMap, Day07, Rule};
+use crate::input::Input;
+use crate::solution::Solution;
+#[cfg(test)]
+use crate::Result;
+
+/// A parser to fuzz.  Each target parses arbitrary bytes, then exercises whatever the parsed value
+/// supports, along with the day's solution over the same bytes.  Errors are expected, since most
+/// inputs are invalid; any panic is a bug.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum FuzzTarget {
+    Receipts,
+    PolicyWithPassword,
+    Area,
+    PassportInput,
+    SeatAssignment,
+    Group,
+    Rule,
+}
+
+impl FuzzTarget {
+    pub const ALL: [FuzzTarget; 7] = [
+        FuzzTarget::Receipts,
+        FuzzTarget::PolicyWithPassword,
+        FuzzTarget::Area,
+        FuzzTarget::PassportInput,
+        FuzzTarget::SeatAssignment,
+        FuzzTarget::Group,
+        FuzzTarget::Rule,
+    ];
+
+    /// The day whose inputs seed the target's corpus (see `fuzz/seed_corpus.sh`).
+    pub fn day(self) -> usize {
+        match self {
+            FuzzTarget::Receipts => 1,
+            FuzzTarget::PolicyWithPassword => 2,
+            FuzzTarget::Area => 3,
+            FuzzTarget::PassportInput => 4,
+            FuzzTarget::SeatAssignment => 5,
+            FuzzTarget::Group => 6,
+            FuzzTarget::Rule => 7,
+        }
+    }
+
+    pub fn run(self, data: &[u8]) {
+        let text = match std::str::from_utf8(data) {
+            Ok(text) => text,
+            // Inputs are always read as UTF-8, so other bytes never reach the parsers:
+            Err(_) => return,
+        };
+        match self {
+            FuzzTarget::Receipts => solve(&Day01, text),
+            FuzzTarget::PolicyWithPassword => {
+                if let Ok(policy) = text.parse::<PolicyWithPassword>() {
+                    let _ = policy.is_valid_part_1();
+                    let _ = policy.is_valid_part_2();
+                }
+                solve(&Day02, text);
+            }
+            FuzzTarget::Area => {
+                if let Ok(area) = text.parse::<Area>() {
+                    let _ = area.is_tree(area.width(), area.height().saturating_sub(1));
+                    let _ = area.count_trees(3, 1);
+                    // Slopes from a single step up to the largest, picked by the data's
+                    // leading bytes:
+                    let step = |byte: Option<&u8>| {
+                        byte.map_or(0, |&byte| usize::MAX >> (u32::from(byte) % usize::BITS))
+                    };
+                    let _ = area.count_trees(step(data.first()), step(data.get(1)));
+                    let _ = area.count_trees(usize::MAX, usize::MAX);
+                }
+                solve(&Day03, text);
+            }
+            FuzzTarget::PassportInput => {
+                if let Ok(passport) = text.parse::<PassportInput>() {
+                    let _ = passport.is_valid_part_1();
+                    let _ = passport.is_valid_part_2();
+                }
+                solve(&Day04, text);
+            }
+            FuzzTarget::SeatAssignment => {
+                if let Ok(assignment) = text.parse::<SeatAssignment>() {
+                    let _ = assignment.get_seat_id();
+                }
+                solve(&Day05, text);
+            }
+            FuzzTarget::Group => {
+                if let Ok(group) = text.parse::<Group>() {
+                    let _ = group.get_any_yes_counts();
+                    let _ = group.get_all_yes_counts();
+                }
+                solve(&Day06, text);
+            }
+            FuzzTarget::Rule => {
+                let _ = text.parse::<Rule>();
+                if let Ok(map) = text.parse::<BagsMap>() {
+                    for bag in map.bags() {
+                        let _ = map.count_containing_bags(bag);
+                        let _ = map.count_item_bags(bag);
+                    }
+                }
+                solve(&Day07, text);
+            }
+        }
+    }
+}
+
+/// The target's name, as used by `cargo fuzz`.
+impl fmt::Display for FuzzTarget {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            FuzzTarget::Receipts => "receipts",
+            FuzzTarget::PolicyWithPassword => "policy_with_password",
+            FuzzTarget::Area => "area",
+            FuzzTarget::PassportInput => "passport_input",
+            FuzzTarget::SeatAssignment => "seat_assignment",
+            FuzzTarget::Group => "group",
+            FuzzTarget::Rule => "rule",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+fn solve<S: Solution>(solution: &S, text: &str) {
+    if let Ok(model) = solution.parse(&Input::from(text)) {
+        let _ = solution.part_1(&model);
+        let _ = solution.part_2(&model);
+    }
+}
+
+#[test]
+fn test_fuzz_targets_on_seeds() -> Result<()> {
+    let inputs = crate::inputs::InputLocator::default();
+    for target in FuzzTarget::ALL.iter().copied() {
+        for seed in &[
+            inputs.puzzle_input(target.day()),
+            inputs.example_input(target.day()),
+        ] {
+            target.run(&std::fs::read(seed)?);
+        }
+    }
+    Ok(())
+}
+
+#[test]
+fn test_fuzz_targets_on_hostile_inputs() {
+    let inputs: &[&[u8]] = &[
+        b"",
+        b"\n\n",
+        b"\xff\xfe",
+        b"4294967295\n4294967295\n",
+        b"0-0 a: a",
+        b"255-255 a: a",
+        b"#\n\n#",
+        b"\xc3\xa9\xc3\xa9\xc3\xa9\xc3\xa9\xc3\xa9",
+        b"FFFFFFFLLR\nFFFFFFFLLR\n",
+        b"byr:99999 byr:1",
+        b"a b bags contain 1 a b bag.",
+        b"a b bags contain 0 c d bags.\nc d bags contain no other bags.",
+        b"contain contain",
+    ];
+    for target in FuzzTarget::ALL.iter().copied() {
+        for input in inputs {
+            target.run(input);
+        }
+    }
+}
+
+#[cfg(test)]
+proptest::proptest! {
+    // A cheap stand-in for `cargo fuzz`, over the characters and words that the inputs are made of:
+    #[test]
+    fn prop_fuzz_targets_never_panic(
+        text in proptest::prop_oneof![
+            "[0-9a-hBFLR #:.,\n-]{0,120}",
+            "([0-9]{1,10}\n){0,8}",
+            "((byr|iyr|eyr|hgt|hcl|ecl|pid|cid):[0-9a-z#]{0,12}[ \n]{1,2}){0,10}",
+            "([ab] [ab] bags contain ([0-9]{1,3} [ab] [ab] bags?(, |.)|no other bags.)\n){0,6}",
+        ]
+    ) {
+        for target in FuzzTarget::ALL.iter().copied() {
+            target.run(text.as_bytes());
+        }
+    }
+}
@@ -0,0 +1,355 @@
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::fmt;
+use std::fs;
+use std::path::Path;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use crate::submit::Feedback;
+use crate::{Error, Result};
+
+/// An answer that was computed or submitted.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct Record {
+    /// Seconds since the unix epoch.
+    pub timestamp: u64,
+    pub year: usize,
+    pub day: usize,
+    pub part: usize,
+    pub answer: String,
+    /// The SHA-256 of the input that the answer is for, when it's known.
+    pub input_hash: Option<String>,
+    /// The website's feedback, when the answer was submitted.
+    pub feedback: Option<Feedback>,
+}
+
+impl Record {
+    /// A computed answer, timestamped now.
+    pub fn new(
+        year: usize,
+        day: usize,
+        part: usize,
+        answer: impl Into<String>,
+        input_hash: Option<String>,
+    ) -> Self {
+        Self {
+            timestamp: now(),
+            year,
+            day,
+            part,
+            answer: answer.into(),
+            input_hash,
+            feedback: None,
+        }
+    }
+
+    fn is_for_part(&self, year: usize, day: usize, part: usize) -> bool {
+        (self.year, self.day, self.part) == (year, day, part)
+    }
+
+    /// Whether the record is about the input with this hash.  An unknown hash matches any input.
+    fn is_for_input(&self, input_hash: Option<&str>) -> bool {
+        match (self.input_hash.as_deref(), input_hash) {
+            (Some(recorded), Some(input_hash)) => recorded == input_hash,
+            _ => true,
+        }
+    }
+}
+
+/// Every answer that was computed or submitted, kept in a JSON file.  The feedback on submitted
+/// answers shows which answers are wrong, and narrows down where the right one is.
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[serde(transparent)]
+pub struct History(Vec<Record>);
+
+impl History {
+    pub const DEFAULT_FILE: &'static str = "history.json";
+
+    /// A missing file has no history yet.
+    pub fn load(path: &Path) -> Result<Self> {
+        let load = || Ok(serde_json::from_str(&fs::read_to_string(path)?)?);
+        match path.exists() {
+            true => load().map_err(|err: Error| err.in_file(path)),
+            false => Ok(Self::default()),
+        }
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        let save = || Ok(fs::write(path, serde_json::to_string_pretty(self)? + "\n")?);
+        save().map_err(|err: Error| err.in_file(path))
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Record> {
+        self.0.iter()
+    }
+
+    pub fn record(&mut self, record: Record) {
+        self.0.push(record);
+    }
+
+    /// The records for a year, or a year's day, or a day's part.
+    pub fn filtered(&self, year: usize, day: Option<usize>, part: Option<usize>) -> Self {
+        Self(
+            self.iter()
+                .filter(|record| record.year == year)
+                .filter(|record| day.is_none_or(|day| record.day == day))
+                .filter(|record| part.is_none_or(|part| record.part == part))
+                .cloned()
+                .collect(),
+        )
+    }
+
+    /// The earlier submission that showed this answer to the part's input to be wrong, if any.
+    pub fn rejection(
+        &self,
+        year: usize,
+        day: usize,
+        part: usize,
+        answer: &str,
+        input_hash: Option<&str>,
+    ) -> Option<&Record> {
+        self.iter().find(|record| {
+            record.is_for_part(year, day, part)
+                && record.is_for_input(input_hash)
+                && record.answer == answer
+                && record.feedback.as_ref().is_some_and(Feedback::is_wrong)
+        })
+    }
+
+    /// How much longer to wait before submitting another answer for the year, when the website
+    /// asked to wait after a submission (at `now`, in seconds since the unix epoch).
+    pub fn wait(&self, year: usize, now: u64) -> Option<Duration> {
+        self.iter()
+            .filter(|record| record.year == year)
+            .filter_map(|record| match &record.feedback {
+                Some(Feedback::Wait(wait)) => Some(record.timestamp + wait.as_secs()),
+                _ => None,
+            })
+            .max()
+            .filter(|&until| until > now)
+            .map(|until| Duration::from_secs(until - now))
+    }
+
+    /// What the too high and too low feedback on the part's input has shown about its answer.
+    pub fn bounds(&self, year: usize, day: usize, part: usize, input_hash: Option<&str>) -> Bounds {
+        self.iter()
+            .filter(|record| record.is_for_part(year, day, part))
+            .filter(|record| record.is_for_input(input_hash))
+            .fold(Bounds::default(), |bounds, record| {
+                match (&record.feedback, record.answer.trim().parse::<i128>()) {
+                    (Some(Feedback::TooLow), Ok(answer)) => Bounds {
+                        lower: bounds.lower.max(Some(answer)),
+                        ..bounds
+                    },
+                    (Some(Feedback::TooHigh), Ok(answer)) => Bounds {
+                        upper: Some(bounds.upper.map_or(answer, |upper| upper.min(answer))),
+                        ..bounds
+                    },
+                    _ => bounds,
+                }
+            })
+    }
+}
+
+impl fmt::Display for History {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "{:<19} | year | day | part | {:>16} | {:<8} | input",
+            "when (UTC)", "answer", "feedback"
+        )?;
+        writeln!(
+            f,
+            "--------------------+------+-----+------+------------------+----------+---------"
+        )?;
+        for record in self.iter() {
+            writeln!(
+                f,
+                "{:<19} | {:>4} | {:>3} | {:>4} | {:>16} | {:<8} | {}",
+                format_timestamp(record.timestamp),
+                record.year,
+                record.day,
+                record.part,
+                record.answer,
+                record
+                    .feedback
+                    .as_ref()
+                    .map_or("-".to_string(), |feedback| feedback.to_string()),
+                record
+                    .input_hash
+                    .as_deref()
+                    .map_or("-", |hash| &hash[..hash.len().min(8)]),
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// An answer is known to be above `lower` and below `upper`, from earlier too low and too high
+/// feedback.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct Bounds {
+    pub lower: Option<i128>,
+    pub upper: Option<i128>,
+}
+
+impl Bounds {
+    /// Explains why the answer must be wrong, when it's outside the bounds.  Answers that aren't
+    /// integers can't be checked.
+    pub fn check(&self, answer: &str) -> Option<String> {
+        let answer = answer.trim().parse::<i128>().ok()?;
+        match (self.lower, self.upper) {
+            (Some(lower), _) if answer <= lower => {
+                Some(format!("you already know the answer is > {}", lower))
+            }
+            (_, Some(upper)) if answer >= upper => {
+                Some(format!("you already know the answer is < {}", upper))
+            }
+            _ => None,
+        }
+    }
+}
+
+/// The hex-encoded SHA-256 of an input.
+pub fn input_hash(input: &[u8]) -> String {
+    format!("{:x}", Sha256::digest(input))
+}
+
+/// Formats seconds since the unix epoch as a UTC date and time, eg: `2020-12-07 05:12:33`.
+fn format_timestamp(timestamp: u64) -> String {
+    let (days, seconds) = (timestamp / 86_400, timestamp % 86_400);
+    // The proleptic Gregorian calendar, from days since the epoch, following
+    // http://howardhinnant.github.io/date_algorithms.html#civil_from_days:
+    let z = days + 719_468;
+    let era = z / 146_097;
+    let day_of_era = z % 146_097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1_460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let shifted_month = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * shifted_month + 2) / 5 + 1;
+    let month = if shifted_month < 10 {
+        shifted_month + 3
+    } else {
+        shifted_month - 9
+    };
+    let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+    format!(
+        "{:04}-{:02}-{:02} {:02}:{:02}:{:02}",
+        year,
+        month,
+        day,
+        seconds / 3_600,
+        seconds % 3_600 / 60,
+        seconds % 60
+    )
+}
+
+/// Seconds since the unix epoch.
+pub(crate) fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |since| since.as_secs())
+}
+
+#[cfg(test)]
+fn submitted(answer: &str, input_hash: Option<&str>, feedback: Feedback) -> Record {
+    Record {
+        feedback: Some(feedback),
+        ..Record::new(2020, 1, 1, answer, input_hash.map(String::from))
+    }
+}
+
+#[test]
+fn test_bounds() {
+    let mut history = History::default();
+    assert_eq!(history.bounds(2020, 1, 1, None), Bounds::default());
+
+    history.record(submitted("1000", Some("abc"), Feedback::TooHigh));
+    history.record(submitted("2000", Some("abc"), Feedback::TooHigh));
+    history.record(submitted("10", Some("abc"), Feedback::TooLow));
+    history.record(submitted("5", None, Feedback::TooLow));
+    history.record(submitted("1", Some("other"), Feedback::TooHigh));
+    history.record(Record::new(2020, 1, 1, "3", Some("abc".into())));
+    history.record(Record {
+        feedback: Some(Feedback::TooHigh),
+        ..Record::new(2021, 1, 1, "2", Some("abc".into()))
+    });
+
+    let bounds = history.bounds(2020, 1, 1, Some("abc"));
+    assert_eq!(
+        bounds,
+        Bounds {
+            lower: Some(10),
+            upper: Some(1000)
+        }
+    );
+    assert_eq!(
+        bounds.check("1000"),
+        Some("you already know the answer is < 1000".to_string())
+    );
+    assert_eq!(
+        bounds.check("10"),
+        Some("you already know the answer is > 10".to_string())
+    );
+    assert_eq!(bounds.check("500"), None);
+    assert_eq!(bounds.check("abc"), None);
+    assert_eq!(history.bounds(2020, 1, 2, Some("abc")), Bounds::default());
+    assert_eq!(history.bounds(2020, 1, 1, Some("other")).upper, Some(1));
+
+    assert!(history.rejection(2020, 1, 1, "2000", Some("abc")).is_some());
+    assert!(history
+        .rejection(2020, 1, 1, "2000", Some("other"))
+        .is_none());
+    assert!(history.rejection(2020, 1, 1, "3", Some("abc")).is_none());
+    assert_eq!(history.bounds(2021, 1, 1, Some("abc")).upper, Some(2));
+
+    // Only a wait that hasn't passed yet holds up the year's submissions:
+    history.record(Record {
+        timestamp: 1_000,
+        ..submitted("4", None, Feedback::Wait(Duration::from_secs(60)))
+    });
+    assert_eq!(history.wait(2020, 1_030), Some(Duration::from_secs(30)));
+    assert_eq!(history.wait(2020, 1_060), None);
+    assert_eq!(history.wait(2021, 1_030), None);
+}
+
+#[test]
+fn test_history_round_trip() -> Result<()> {
+    let path = std::env::temp_dir().join(format!("aoc_history_{}.json", std::process::id()));
+    assert_eq!(History::load(&path)?.iter().count(), 0);
+
+    let mut history = History::default();
+    history.record(submitted("4", None, Feedback::TooLow));
+    history.record(Record::new(2020, 7, 2, "32", Some(input_hash(b""))));
+    history.save(&path)?;
+    let loaded = History::load(&path)?;
+    assert_eq!(
+        loaded.iter().collect::<Vec<_>>(),
+        history.iter().collect::<Vec<_>>()
+    );
+    assert_eq!(loaded.filtered(2020, Some(7), None).iter().count(), 1);
+    assert_eq!(loaded.filtered(2020, Some(7), Some(1)).iter().count(), 0);
+    assert_eq!(loaded.filtered(2021, None, None).iter().count(), 0);
+
+    fs::write(&path, "garbage")?;
+    let err = History::load(&path).unwrap_err();
+    assert!(err
+        .chain()
+        .to_string()
+        .starts_with(&format!("Error in `{}`: JSON Error", path.display())));
+
+    fs::remove_file(&path)?;
+    Ok(())
+}
+
+#[test]
+fn test_input_hash_and_timestamps() {
+    assert_eq!(
+        input_hash(b""),
+        "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855"
+    );
+    assert_eq!(format_timestamp(0), "1970-01-01 00:00:00");
+    assert_eq!(format_timestamp(951_782_400), "2000-02-29 00:00:00");
+    assert_eq!(format_timestamp(1_607_317_953), "2020-12-07 05:12:33");
+}
@@ -0,0 +1,342 @@
+use std::io::{BufReader, Read};
+use std::str::FromStr;
+
+use crate::error::SpanError;
+#[cfg(test)]
+use crate::inputs::InputSource;
+use crate::{Error, Result};
+#[cfg(test)]
+use std::path::PathBuf;
+
+/// A day's puzzle input, read into memory once so that each solver can slice it up into lines,
+/// paragraphs or a grid.  The helpers number lines from 1, and attach that number to any error.
+/// Errors from parsing a single line point at the offending part of it with a `SpanError`.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Input {
+    text: String,
+}
+
+/// A single line of the input, without its line ending.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Line<'a> {
+    /// The 1-indexed line number.
+    pub number: usize,
+    pub text: &'a str,
+}
+
+/// A run of consecutive non-blank lines, separated from the next paragraph by a blank line.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Paragraph<'a> {
+    /// The 1-indexed line number of the paragraph's first line.
+    pub number: usize,
+    /// The paragraph's lines, joined by their original line endings.
+    pub text: &'a str,
+}
+
+/// A rectangular grid of cells, stored row by row.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    cells: Vec<T>,
+}
+
+impl Input {
+    pub fn new(text: impl Into<String>) -> Self {
+        Self { text: text.into() }
+    }
+
+    pub fn from_reader(mut buf_reader: BufReader<Box<dyn Read + '_>>) -> Result<Self> {
+        let mut text = String::new();
+        buf_reader.read_to_string(&mut text)?;
+        Ok(Self { text })
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.text
+    }
+
+    pub fn lines(&self) -> impl Iterator<Item = Line<'_>> {
+        self.text.lines().enumerate().map(|(i, text)| Line {
+            number: i + 1,
+            text,
+        })
+    }
+
+    /// Splits the input on blank lines.  Leading, trailing and repeated blank lines don't produce
+    /// empty paragraphs.
+    pub fn paragraphs(&self) -> impl Iterator<Item = Paragraph<'_>> {
+        let text = self.text.as_str();
+        let mut paragraphs = Vec::new();
+        let mut start: Option<(usize, usize)> = None;
+        let mut offset = 0;
+        for (i, raw_line) in text.split_inclusive('\n').enumerate() {
+            let line = raw_line.trim_end_matches(&['\n', '\r'][..]);
+            match (start, line.trim().is_empty()) {
+                (None, false) => start = Some((i + 1, offset)),
+                (Some((number, begin)), true) => {
+                    paragraphs.push(Paragraph {
+                        number,
+                        text: text[begin..offset].trim_end_matches(&['\n', '\r'][..]),
+                    });
+                    start = None;
+                }
+                _ => {}
+            }
+            offset += raw_line.len();
+        }
+        if let Some((number, begin)) = start {
+            paragraphs.push(Paragraph {
+                number,
+                text: text[begin..].trim_end_matches(&['\n', '\r'][..]),
+            });
+        }
+        paragraphs.into_iter()
+    }
+
+    /// Parses every line into a `T`.
+    pub fn parse_lines<T>(&self) -> Result<Vec<T>>
+    where
+        T: FromStr,
+        Error: From<T::Err>,
+    {
+        self.lines()
+            .map(|line| line.parse())
+            .collect::<Result<Vec<T>>>()
+    }
+
+    /// Parses every line into an integer, ignoring surrounding whitespace.
+    pub fn integers<T>(&self) -> Result<Vec<T>>
+    where
+        T: FromStr<Err = std::num::ParseIntError>,
+    {
+        self.lines()
+            .map(|line| {
+                SpanError::parse_within(line.text, line.text.trim())
+                    .map_err(|err| err.at_line(line.number))
+            })
+            .collect()
+    }
+
+    /// Parses every paragraph into a `T`.
+    pub fn parse_paragraphs<T>(&self) -> Result<Vec<T>>
+    where
+        T: FromStr,
+        Error: From<T::Err>,
+    {
+        self.paragraphs()
+            .map(|paragraph| {
+                paragraph
+                    .text
+                    .parse()
+                    .map_err(|err| Error::from(err).at_line(paragraph.number))
+            })
+            .collect()
+    }
+
+    /// Converts each character into a cell.  Every line must have the same width.
+    pub fn grid<T, F>(&self, cell: F) -> Result<Grid<T>>
+    where
+        F: Fn(char) -> Result<T>,
+    {
+        let mut width = None;
+        let mut height = 0;
+        let mut cells = Vec::new();
+        for line in self.lines() {
+            let row = line
+                .text
+                .char_indices()
+                .map(|(i, c)| {
+                    cell(c).map_err(|err| {
+                        Error::from(SpanError {
+                            cause: Some(Box::new(err)),
+                            ..SpanError::new(
+                                format!("invalid grid cell `{}`", c),
+                                line.text,
+                                i..i + c.len_utf8(),
+                            )
+                        })
+                        .at_line(line.number)
+                    })
+                })
+                .collect::<Result<Vec<T>>>()?;
+            match width {
+                None => width = Some(row.len()),
+                Some(width) if width != row.len() => {
+                    // Point at the extra cells, or just past the end of a short row:
+                    let start = line
+                        .text
+                        .char_indices()
+                        .nth(width)
+                        .map_or(line.text.len(), |(i, _)| i);
+                    return Err(Error::from(SpanError::new(
+                        format!(
+                            "expected every row of the grid to have width {}, found {}",
+                            width,
+                            row.len()
+                        ),
+                        line.text,
+                        start..line.text.len(),
+                    ))
+                    .at_line(line.number));
+                }
+                _ => {}
+            }
+            cells.extend(row);
+            height += 1;
+        }
+        Ok(Grid {
+            width: width.unwrap_or(0),
+            height,
+            cells,
+        })
+    }
+}
+
+impl From<&str> for Input {
+    fn from(text: &str) -> Self {
+        Self::new(text)
+    }
+}
+
+impl Line<'_> {
+    pub fn parse<T>(&self) -> Result<T>
+    where
+        T: FromStr,
+        Error: From<T::Err>,
+    {
+        SpanError::parse_within(self.text, self.text).map_err(|err| err.at_line(self.number))
+    }
+}
+
+impl<T> Grid<T> {
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// Returns `None` when the coordinate is off the grid.
+    pub fn get(&self, x: usize, y: usize) -> Option<&T> {
+        if x < self.width && y < self.height {
+            self.cells.get(y * self.width + x)
+        } else {
+            None
+        }
+    }
+
+    /// Iterates over the cells row by row, along with their `(x, y)` coordinates.
+    pub fn cells(&self) -> impl Iterator<Item = ((usize, usize), &T)> {
+        let width = self.width;
+        self.cells
+            .iter()
+            .enumerate()
+            .map(move |(i, cell)| ((i % width, i / width), cell))
+    }
+}
+
+/// A utility function for tests, which reads the whole input from a file (if `Some`) or stdin.
+#[cfg(test)]
+pub fn read_input(path_buf: Option<PathBuf>) -> Result<Input> {
+    let source = path_buf.map_or(InputSource::Stdin, InputSource::File);
+    Input::from_reader(source.open()?)
+}
+
+#[test]
+fn test_lines() -> Result<()> {
+    let input = Input::from("1721\n979\r\n366\n");
+    assert_eq!(
+        input.lines().collect::<Vec<Line<'_>>>(),
+        vec![
+            Line {
+                number: 1,
+                text: "1721"
+            },
+            Line {
+                number: 2,
+                text: "979"
+            },
+            Line {
+                number: 3,
+                text: "366"
+            },
+        ]
+    );
+    assert_eq!(input.integers::<u32>()?, vec![1721, 979, 366]);
+    assert_eq!(input.parse_lines::<u32>()?, vec![1721, 979, 366]);
+    Ok(())
+}
+
+#[test]
+fn test_line_errors() -> Result<()> {
+    let input = Input::from("1721\n979\n x366\n");
+    let span = |result: Result<Vec<u32>>| {
+        let err = result.unwrap_err();
+        err.span().map(|span| (span.line, span.columns.clone()))
+    };
+    assert_eq!(span(input.integers()), Some((3, 1..5)));
+    assert_eq!(span(input.parse_lines()), Some((3, 0..5)));
+    Ok(())
+}
+
+#[test]
+fn test_paragraphs() -> Result<()> {
+    let input = Input::from("\nabc\n\na\nb\nc\n\n\nab\nac\n");
+    assert_eq!(
+        input.paragraphs().collect::<Vec<Paragraph<'_>>>(),
+        vec![
+            Paragraph {
+                number: 2,
+                text: "abc"
+            },
+            Paragraph {
+                number: 4,
+                text: "a\nb\nc"
+            },
+            Paragraph {
+                number: 9,
+                text: "ab\nac"
+            },
+        ]
+    );
+    assert!(matches!(
+        Input::from("1\n\n2\n\nx\n").parse_paragraphs::<u32>(),
+        Err(Error::LineError { line: 5, .. })
+    ));
+    Ok(())
+}
+
+#[test]
+fn test_grid() -> Result<()> {
+    let cell = |c| match c {
+        '#' => Ok(true),
+        '.' => Ok(false),
+        c => Err(Error::InvalidInput(format!("invalid cell: {}", c))),
+    };
+    let grid = Input::from("..#\n#..\n").grid(cell)?;
+    assert_eq!((grid.width(), grid.height()), (3, 2));
+    assert_eq!(grid.get(2, 0), Some(&true));
+    assert_eq!(grid.get(0, 1), Some(&true));
+    assert_eq!(grid.get(3, 0), None);
+    assert_eq!(grid.get(0, 2), None);
+    assert_eq!(grid.get(0, usize::MAX), None);
+    assert_eq!(grid.cells().filter(|(_, &tree)| tree).count(), 2);
+
+    let span = |input: &str| {
+        let err = Input::from(input).grid(cell).unwrap_err();
+        err.span().map(|span| (span.line, span.columns.clone()))
+    };
+    assert_eq!(span("..#\n#.\n"), Some((2, 2..2)));
+    assert_eq!(span("..#\n#...\n"), Some((2, 3..4)));
+    assert_eq!(span("..#\n#.x\n"), Some((2, 2..3)));
+    Ok(())
+}
+
+#[test]
+fn test_read_input() -> Result<()> {
+    let input = read_input(Some(PathBuf::from("./inputs/day_01.example.txt")))?;
+    assert_eq!(input.lines().count(), 6);
+    Ok(())
+}
@@ -0,0 +1,138 @@
+use std::fs::{self, File};
+use std::io::{self, stdin, BufReader, Read};
+use std::path::{Path, PathBuf};
+
+use crate::exercises::DEFAULT_YEAR;
+use crate::history::input_hash;
+
+/// Finds each day's input by convention, so that the input path can be omitted: the puzzle input
+/// for day 1 is `<dir>/day_01.txt`, and its example is `<dir>/day_01.example.txt`.  Another year's
+/// inputs are in a subdirectory named after the year, eg: `<dir>/2021/day_01.txt`.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct InputLocator {
+    dir: PathBuf,
+    year: usize,
+}
+
+impl Default for InputLocator {
+    fn default() -> Self {
+        Self::new("inputs")
+    }
+}
+
+impl InputLocator {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self {
+            dir: dir.into(),
+            year: DEFAULT_YEAR,
+        }
+    }
+
+    /// Locates the given year's inputs instead.
+    pub fn with_year(self, year: usize) -> Self {
+        Self { year, ..self }
+    }
+
+    pub fn year(&self) -> usize {
+        self.year
+    }
+
+    /// The directory holding the year's inputs.
+    pub fn dir(&self) -> PathBuf {
+        match self.year {
+            DEFAULT_YEAR => self.dir.clone(),
+            year => self.dir.join(year.to_string()),
+        }
+    }
+
+    pub fn puzzle_input(&self, day: usize) -> PathBuf {
+        self.dir().join(format!("day_{:02}.txt", day))
+    }
+
+    pub fn example_input(&self, day: usize) -> PathBuf {
+        self.dir().join(format!("day_{:02}.example.txt", day))
+    }
+
+    /// An explicit path always wins, and `-` selects stdin.  Otherwise, the day's example or
+    /// puzzle input is used.
+    pub fn resolve(&self, day: usize, path: Option<PathBuf>, example: bool) -> InputSource {
+        match path {
+            Some(path) if path == Path::new("-") => InputSource::Stdin,
+            Some(path) => InputSource::File(path),
+            None if example => InputSource::File(self.example_input(day)),
+            None => InputSource::File(self.puzzle_input(day)),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum InputSource {
+    Stdin,
+    File(PathBuf),
+}
+
+impl InputSource {
+    pub fn open(&self) -> io::Result<BufReader<Box<dyn Read>>> {
+        let reader: Box<dyn Read> = match self {
+            Self::Stdin => Box::new(stdin()),
+            Self::File(path) => Box::new(File::open(path)?),
+        };
+        Ok(BufReader::new(reader))
+    }
+
+    /// The SHA-256 of the input file.  Stdin can only be read once, so its hash isn't known.
+    pub fn hash(&self) -> Option<String> {
+        match self {
+            Self::Stdin => None,
+            Self::File(path) => fs::read(path).ok().map(|input| input_hash(&input)),
+        }
+    }
+}
+
+#[test]
+fn test_resolve() {
+    let locator = InputLocator::new("puzzles");
+    assert_eq!(
+        locator.resolve(3, None, false),
+        InputSource::File("puzzles/day_03.txt".into())
+    );
+    assert_eq!(
+        locator.resolve(12, None, true),
+        InputSource::File("puzzles/day_12.example.txt".into())
+    );
+    assert_eq!(
+        locator.resolve(3, Some("other.txt".into()), true),
+        InputSource::File("other.txt".into())
+    );
+    assert_eq!(
+        locator.resolve(3, Some("-".into()), false),
+        InputSource::Stdin
+    );
+}
+
+#[test]
+fn test_years() {
+    let locator = InputLocator::new("puzzles").with_year(2021);
+    assert_eq!(locator.year(), 2021);
+    assert_eq!(
+        locator.puzzle_input(3),
+        Path::new("puzzles/2021/day_03.txt")
+    );
+    assert_eq!(
+        locator.with_year(DEFAULT_YEAR).puzzle_input(3),
+        Path::new("puzzles/day_03.txt")
+    );
+}
+
+#[test]
+fn test_default_inputs_exist() {
+    for metadata in crate::exercises::SOLUTIONS
+        .iter()
+        .map(|solver| solver.metadata())
+    {
+        let locator = InputLocator::default().with_year(metadata.year);
+        let day = metadata.day;
+        assert!(locator.puzzle_input(day).is_file(), "day {}", day);
+        assert!(locator.example_input(day).is_file(), "day {}", day);
+    }
+}
@@ -4,31 +4,129 @@
     rust_2018_idioms,
     missing_doc_code_examples
 )]
-#![feature(iterator_fold_self)]
 // To use the `unsafe` keyword, change to `#![allow(unsafe_code)]` (do not remove); aids auditing.
 #![forbid(unsafe_code)]
 
 #[macro_use]
 extern crate lazy_static;
 
-use aoc_result::AocReturn;
-pub use args::Args;
-pub use error::Error;
-use exercises::{day_01, day_02, day_03, day_04, day_05, day_06, day_07};
-use option_ext::convert_path_buf;
+pub use aoc_result::AocReturn;
+pub use args::{Args, Command};
+pub use bench::{Baseline, Benchmark, Comparison, Stats, DEFAULT_ITERATIONS};
+pub use client::{fetch, Client, Fetched, Session, DEFAULT_BASE_URL};
+pub use config::{BenchConfig, Config, DayConfig};
+pub use error::{Chain, Error, ExitCode, SpanError};
+pub use exercises::DEFAULT_YEAR;
+pub use format::{render, Format, Report};
+#[cfg(feature = "fuzzing")]
+pub use fuzz::FuzzTarget;
+pub use history::{input_hash, Bounds, History, Record};
+#[cfg(test)]
+use input::read_input;
+pub use input::{Grid, Input, Line, Paragraph};
+pub use inputs::{InputLocator, InputSource};
+pub use logging::init_tracing;
+pub use repl::{run as run_repl, Repl};
+pub use runner::{run_all, DayRange, Run, Summary};
+pub use scaffold::{next_day, scaffold};
+pub use solution::{Answers, Metadata, Solution, Solver};
 use std::io::{BufReader, Read};
-use vec_ext::VecExt;
+pub use submit::{submit, Feedback};
+pub use timing::Timing;
+pub use verify::{verify, Entry, Expected, Manifest, Verdict, Verification, Verifications};
+pub use watch::{check, Check, Watcher};
 
 mod aoc_result;
 mod args;
-mod exercises;
-mod option_ext;
+mod bench;
+mod client;
+mod config;
+pub mod exercises;
+mod format;
+#[cfg(any(test, feature = "fuzzing"))]
+mod fuzz;
+mod history;
+mod input;
+mod inputs;
+mod logging;
+mod repl;
+mod runner;
+mod scaffold;
+mod solution;
+mod submit;
+mod timing;
 mod vec_ext;
+mod verify;
+mod watch;
 
 pub type Result<T, E = Error> = std::result::Result<T, E>;
 
 mod error;
 
+/// Returns the metadata of every implemented day, ordered by year, then day.
+pub fn implemented_days() -> impl Iterator<Item = Metadata> {
+    let mut days = exercises::SOLUTIONS
+        .iter()
+        .map(|solver| solver.metadata())
+        .collect::<Vec<Metadata>>();
+    days.sort_by_key(|metadata| (metadata.year, metadata.day));
+    days.into_iter()
+}
+
+fn find_solver(year: usize, day: usize, part: usize) -> Result<&'static dyn Solver> {
+    if year < 2015 {
+        return Err(Error::InvalidYearArg(year));
+    }
+    if !(1..=25).contains(&day) || !(1..=2).contains(&part) {
+        return Err(Error::InvalidDayOrPartArg(day, part));
+    }
+    exercises::find(year, day).ok_or(Error::UnimplementedDay(year, day))
+}
+
+/// Checks that the day's part is valid and implemented, eg: before its input is read.
+pub fn ensure_implemented(year: usize, day: usize, part: usize) -> Result<()> {
+    find_solver(year, day, part).map(|_| ())
+}
+
+pub fn aoc(
+    year: usize,
+    day: usize,
+    part: usize,
+    buf_reader: BufReader<Box<dyn Read>>,
+) -> Result<AocReturn> {
+    find_solver(year, day, part)?.run(part, buf_reader)
+}
+
+/// Like `aoc`, but also reports how long the parse and solve phases took.
+pub fn aoc_timed(
+    year: usize,
+    day: usize,
+    part: usize,
+    buf_reader: BufReader<Box<dyn Read>>,
+) -> Result<(AocReturn, Timing)> {
+    find_solver(year, day, part)?.run_timed(part, buf_reader)
+}
+
+/// Parses the day's input once, and answers both parts from it.
+pub fn aoc_both_timed(
+    year: usize,
+    day: usize,
+    buf_reader: BufReader<Box<dyn Read>>,
+) -> Result<Answers> {
+    find_solver(year, day, 1)?.run_both_timed(buf_reader)
+}
+
+/// Runs the day's part `iterations` times, and summarizes how long each phase took.
+pub fn bench(
+    year: usize,
+    day: usize,
+    part: usize,
+    source: &InputSource,
+    iterations: usize,
+) -> Result<Benchmark> {
+    bench::bench(find_solver(year, day, part)?, part, source, iterations)
+}
+
 #[cfg(test)]
 mod tests {
     #[test]
@@ -36,23 +134,3 @@ mod tests {
         assert_eq!(2 + 2, 4);
     }
 }
-
-pub fn aoc(day: usize, part: usize, buf_reader: BufReader<Box<dyn Read>>) -> Result<AocReturn> {
-    match (day, part) {
-        (1, 1) => day_01::part_1(buf_reader).map(|v| v.into()),
-        (1, 2) => day_01::part_2(buf_reader).map(|v| v.into()),
-        (2, 1) => day_02::part_1(buf_reader).map(|v| v.into()),
-        (2, 2) => day_02::part_2(buf_reader).map(|v| v.into()),
-        (3, 1) => day_03::part_1(buf_reader).map(|v| v.into()),
-        (3, 2) => day_03::part_2(buf_reader).map(|v| v.into()),
-        (4, 1) => day_04::part_1(buf_reader).map(|v| v.into()),
-        (4, 2) => day_04::part_2(buf_reader).map(|v| v.into()),
-        (5, 1) => day_05::part_1(buf_reader).map(|v| v.into()),
-        (5, 2) => day_05::part_2(buf_reader).map(|v| v.into()),
-        (6, 1) => day_06::part_1(buf_reader).map(|v| v.into()),
-        (6, 2) => day_06::part_2(buf_reader).map(|v| v.into()),
-        (7, 1) => day_07::part_1(buf_reader).map(|v| v.into()),
-        (7, 2) => day_07::part_2(buf_reader).map(|v| v.into()),
-        _ => Err(Error::InvalidDayOrPartArg(day, part)),
-    }
-}
@@ -0,0 +1,29 @@
+use tracing_subscriber::filter::LevelFilter;
+
+/// Sends the solvers' tracing events to stderr, so that they never mix with the answers on stdout.
+/// Without `-v` only warnings are shown, `-v` adds debug events, and `-vv` adds trace events (eg:
+/// every pointer move in day 1).
+pub fn init_tracing(verbosity: u8) {
+    // A subscriber may already be set (eg: by an embedding program), in which case it's kept:
+    let _ = tracing_subscriber::fmt()
+        .with_max_level(level(verbosity))
+        .with_writer(std::io::stderr)
+        .without_time()
+        .try_init();
+}
+
+fn level(verbosity: u8) -> LevelFilter {
+    match verbosity {
+        0 => LevelFilter::WARN,
+        1 => LevelFilter::DEBUG,
+        _ => LevelFilter::TRACE,
+    }
+}
+
+#[test]
+fn test_level() {
+    assert_eq!(level(0), LevelFilter::WARN);
+    assert_eq!(level(1), LevelFilter::DEBUG);
+    assert_eq!(level(2), LevelFilter::TRACE);
+    assert_eq!(level(5), LevelFilter::TRACE);
+}
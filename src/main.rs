@@ -1,15 +1,387 @@
-use modulitos_2020::{Args, Result};
+use modulitos_2020::{
+    Answers, AocReturn, Args, Baseline, Client, Command, Config, Error, ExitCode, Feedback,
+    Fetched, History, InputLocator, InputSource, Manifest, Record, Repl, Report, Result, Session,
+    Timing, Watcher,
+};
+use std::path::Path;
+use std::time::Duration;
+use structopt::clap::{Error as ClapError, ErrorKind};
 use structopt::StructOpt;
 
-use option_ext::OptionExt;
-
-mod option_ext;
-
-fn main() -> Result<()> {
+fn main() {
     // Parses our cli args into a shared common struct:
-    let args = Args::from_args();
-    let res = modulitos_2020::aoc(args.day, args.part, args.input_data_file.try_into()?)?;
-    println!("answer is: {:?}", res);
+    let args = Args::from_iter_safe(std::env::args_os()).unwrap_or_else(|err| usage_error(err));
+    if let Err(err) = run(args) {
+        report(&err);
+        err.exit_code().exit();
+    }
+}
+
+fn run(args: Args) -> Result<()> {
+    modulitos_2020::init_tracing(args.verbose);
+    let args = Config::load(args.config.as_deref())?.apply(args);
+    let year = args.year.unwrap_or(modulitos_2020::DEFAULT_YEAR);
+    let inputs = args
+        .input_dir
+        .clone()
+        .map(InputLocator::new)
+        .unwrap_or_default()
+        .with_year(year);
+    let example = args.example;
+    let session_file = args
+        .session_file
+        .clone()
+        .unwrap_or_else(|| Session::DEFAULT_FILE.into());
+    let base_url = args
+        .base_url
+        .clone()
+        .unwrap_or_else(|| modulitos_2020::DEFAULT_BASE_URL.into());
+    let history_file = args
+        .history
+        .clone()
+        .unwrap_or_else(|| History::DEFAULT_FILE.into());
+    match args {
+        Args {
+            command: Some(Command::List),
+            ..
+        } => {
+            for metadata in modulitos_2020::implemented_days() {
+                println!(
+                    "{} day {:>2}: {}",
+                    metadata.year, metadata.day, metadata.title
+                );
+            }
+        }
+        Args {
+            command: Some(Command::All { days, jobs }),
+            format,
+            ..
+        } => {
+            let summary =
+                modulitos_2020::run_all(&days.unwrap_or_default(), &inputs, example, jobs)?;
+            match format {
+                Some(format) => print!("{}", modulitos_2020::render(format, &summary.reports())?),
+                None => print!("{}", summary),
+            }
+            if summary.failures() > 0 {
+                ExitCode::Failure.exit();
+            }
+        }
+        Args {
+            command:
+                Some(Command::Bench {
+                    days,
+                    part,
+                    input_data_file,
+                    iterations,
+                    baseline,
+                    save_baseline,
+                    threshold,
+                }),
+            ..
+        } => {
+            let days = days.unwrap_or_default();
+            // A single day must be implemented, while a range covers whichever days are:
+            let days = match days.days().count() {
+                1 => vec![*days.days().start()],
+                _ => modulitos_2020::implemented_days()
+                    .filter(|metadata| metadata.year == year && days.contains(metadata.day))
+                    .map(|metadata| metadata.day)
+                    .collect(),
+            };
+            if input_data_file.is_some() && days.len() > 1 {
+                return Err(Error::InvalidInput(
+                    "an input file can only be benchmarked for a single day".into(),
+                ));
+            }
+            let parts = part.map_or(vec![1, 2], |part| vec![part]);
+            let iterations = iterations.unwrap_or(modulitos_2020::DEFAULT_ITERATIONS);
+            let baseline = baseline.map(|path| Baseline::load(&path)).transpose()?;
+            let mut saved = save_baseline.as_deref().map(Baseline::load).transpose()?;
+            let mut regressions = 0;
+            for day in days {
+                for &part in &parts {
+                    modulitos_2020::ensure_implemented(year, day, part)?;
+                    let source = inputs.resolve(day, input_data_file.clone(), example);
+                    let benchmark = modulitos_2020::bench(year, day, part, &source, iterations)?;
+                    print!("{}", benchmark);
+                    let comparison = baseline
+                        .as_ref()
+                        .and_then(|baseline| baseline.compare(&benchmark, threshold));
+                    if let Some(comparison) = comparison {
+                        println!("{}", comparison);
+                        if comparison.is_regression() {
+                            regressions += 1;
+                        }
+                    }
+                    if let Some(saved) = &mut saved {
+                        saved.record(benchmark);
+                    }
+                }
+            }
+            if let (Some(path), Some(saved)) = (save_baseline, saved) {
+                saved.save(&path)?;
+            }
+            if regressions > 0 {
+                eprintln!("{} part(s) regressed beyond the threshold", regressions);
+                ExitCode::Failure.exit();
+            }
+        }
+        Args {
+            command: Some(Command::New { day, title }),
+            ..
+        } => {
+            let day = day.unwrap_or_else(|| modulitos_2020::next_day(year));
+            for path in modulitos_2020::scaffold(Path::new("."), &inputs, day, &title)? {
+                println!("created {}", path.display());
+            }
+            println!("registered day {} in src/exercises.rs", day);
+        }
+        Args {
+            command: Some(Command::Fetch { days }),
+            ..
+        } => {
+            let client = Client::new(&base_url, Session::load(&session_file)?);
+            for day in days.days() {
+                match modulitos_2020::fetch(&client, &inputs, day)? {
+                    Fetched::Cached(path) => println!("cached {}", path.display()),
+                    Fetched::Downloaded(path) => println!("downloaded {}", path.display()),
+                }
+            }
+        }
+        Args {
+            command: Some(Command::Submit { day, part, answer }),
+            ..
+        } => {
+            let source = inputs.resolve(day, None, example);
+            let answer = match answer {
+                Some(answer) => answer,
+                None => {
+                    modulitos_2020::ensure_implemented(year, day, part)?;
+                    modulitos_2020::aoc(year, day, part, source.open()?)?.to_string()
+                }
+            };
+            let client = Client::new(&base_url, Session::load(&session_file)?);
+            let mut history = History::load(&history_file)?;
+            let feedback = modulitos_2020::submit(
+                &client,
+                &mut history,
+                year,
+                day,
+                part,
+                &answer,
+                source.hash(),
+            )?;
+            history.save(&history_file)?;
+            println!("day {}, part {}: `{}` is {}", day, part, answer, feedback);
+            if feedback.is_wrong() || matches!(feedback, Feedback::Wait(_)) {
+                ExitCode::Failure.exit();
+            }
+        }
+        Args {
+            command: Some(Command::History { day, part }),
+            ..
+        } => {
+            print!(
+                "{}",
+                History::load(&history_file)?.filtered(year, day, part)
+            );
+        }
+        Args {
+            command:
+                Some(Command::Watch {
+                    day,
+                    part,
+                    input_data_file,
+                    manifest,
+                    interval,
+                }),
+            ..
+        } => {
+            modulitos_2020::ensure_implemented(year, day, part.unwrap_or(1))?;
+            let input = match inputs.resolve(day, input_data_file, example) {
+                InputSource::File(input) => input,
+                InputSource::Stdin => {
+                    return Err(Error::InvalidInput(
+                        "watching needs an input file, since stdin can only be read once".into(),
+                    ))
+                }
+            };
+            let source = InputSource::File(input.clone());
+            let mut watcher = Watcher::new(vec![input, manifest.clone()]);
+            let mut runs = 0;
+            loop {
+                if watcher.changed() {
+                    if runs > 0 {
+                        println!("---");
+                    }
+                    runs += 1;
+                    // Errors are reported without stopping, since they may be fixed by the next
+                    // change:
+                    let manifest = match manifest.is_file() {
+                        true => Manifest::from_path(&manifest)
+                            .map_err(|err| eprintln!("error: {}", err.chain()))
+                            .ok(),
+                        false => None,
+                    };
+                    match modulitos_2020::check(year, day, part, &source, manifest.as_ref()) {
+                        Ok(checks) => checks.iter().for_each(|check| println!("{}", check)),
+                        Err(err) => eprintln!("error: {}", err.chain()),
+                    }
+                }
+                std::thread::sleep(Duration::from_millis(interval));
+            }
+        }
+        Args {
+            command:
+                Some(Command::Repl {
+                    day,
+                    input_data_file,
+                }),
+            ..
+        } => {
+            let repl = Repl::load(year, day, &inputs.resolve(day, input_data_file, example))?;
+            eprintln!(
+                "loaded day {}; `help` lists the commands, and `quit` exits",
+                day
+            );
+            modulitos_2020::run_repl(&repl, std::io::stdin().lock(), std::io::stdout())?;
+        }
+        Args {
+            command: Some(Command::Verify { manifest }),
+            ..
+        } => {
+            let verifications = modulitos_2020::verify(&Manifest::from_path(&manifest)?);
+            print!("{}", verifications);
+            if verifications.failures() > 0 {
+                ExitCode::Failure.exit();
+            }
+        }
+        Args {
+            day: Some(day),
+            part,
+            input_data_file,
+            format,
+            ..
+        } => {
+            modulitos_2020::ensure_implemented(year, day, part.unwrap_or(1))?;
+            let source = inputs.resolve(day, input_data_file, example);
+            // Without a part, the input is parsed once and both parts are answered from it:
+            let outcomes = match part {
+                Some(part) => vec![(
+                    part,
+                    modulitos_2020::aoc_timed(year, day, part, source.open()?),
+                )],
+                None => modulitos_2020::aoc_both_timed(year, day, source.open()?)
+                    .unwrap_or_else(|err| Answers::failed(day, err))
+                    .into_parts()
+                    .collect(),
+            };
+            match format {
+                Some(format) => {
+                    let reports = outcomes
+                        .iter()
+                        .map(|(part, outcome)| Report::new(year, day, *part, outcome))
+                        .collect::<Vec<Report>>();
+                    print!("{}", modulitos_2020::render(format, &reports)?);
+                }
+                None => {
+                    for (part_number, outcome) in &outcomes {
+                        let (res, timing) = match outcome {
+                            Ok((res, timing)) => (res, timing),
+                            Err(err) => {
+                                report(err);
+                                break;
+                            }
+                        };
+                        if part.is_none() {
+                            print!("part {} ", part_number);
+                        }
+                        println!("answer is: {}", res);
+                        println!("took {:?} ({})", timing.total(), timing);
+                    }
+                }
+            }
+            // The answers are already out, so they aren't lost when the history can't be updated:
+            if let Err(err) = record_answers(&history_file, year, day, &outcomes, source.hash()) {
+                eprintln!("warning: the answers weren't recorded: {}", err.chain());
+            }
+            if let Some(err) = outcomes
+                .iter()
+                .find_map(|(_, outcome)| outcome.as_ref().err())
+            {
+                err.exit_code().exit();
+            }
+            if outcomes.iter().any(|(_, outcome)| {
+                outcome
+                    .as_ref()
+                    .is_ok_and(|(answer, _)| !answer.is_solved())
+            }) {
+                ExitCode::Unsolved.exit();
+            }
+        }
+        _ => usage_error(ClapError::with_description(
+            "the day is required unless a subcommand is given",
+            ErrorKind::MissingRequiredArgument,
+        )),
+    }
 
     Ok(())
-}
\ No newline at end of file
+}
+
+/// Prints the error and its causes to stderr.  A parse error points at the offending input.
+fn report(err: &Error) {
+    match err.span() {
+        Some(span) => {
+            eprint!("{}", span.render());
+            if let Some(cause) = &span.cause {
+                eprintln!("  caused by: {}", cause.chain());
+            }
+        }
+        None => eprintln!("error: {:#}", err.chain()),
+    }
+}
+
+/// Exits with the usage exit code on an invalid command line.  Help and version requests aren't
+/// errors, so they still exit successfully.
+fn usage_error(err: ClapError) -> ! {
+    if !err.use_stderr() {
+        err.exit();
+    }
+    eprintln!("{}", err.message);
+    ExitCode::Usage.exit()
+}
+
+/// Records each answer in the history file, warning about any that earlier feedback shows to be
+/// wrong.
+fn record_answers(
+    history_file: &Path,
+    year: usize,
+    day: usize,
+    outcomes: &[(usize, Result<(AocReturn, Timing)>)],
+    input_hash: Option<String>,
+) -> Result<()> {
+    let mut history = History::load(history_file)?;
+    for (part, outcome) in outcomes {
+        if let Ok((answer, _)) = outcome {
+            let answer = answer.to_string();
+            let reason = match history.rejection(year, day, *part, &answer, input_hash.as_deref()) {
+                Some(rejected) => rejected
+                    .feedback
+                    .as_ref()
+                    .map(|feedback| format!("it was already submitted, and was {}", feedback)),
+                None => history
+                    .bounds(year, day, *part, input_hash.as_deref())
+                    .check(&answer),
+            };
+            if let Some(reason) = reason {
+                eprintln!(
+                    "warning: day {}, part {}: `{}` is wrong, since {}",
+                    day, part, answer, reason
+                );
+            }
+            history.record(Record::new(year, day, *part, answer, input_hash.clone()));
+        }
+    }
+    history.save(history_file)
+}
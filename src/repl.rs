@@ -0,0 +1,236 @@
+use std::io::{BufRead, Write};
+
+use crate::exercises::day_04::{Day04, PassportInput};
+use crate::exercises::day_07::{Bag, BagsMap, Day07};
+use crate::exercises::DEFAULT_YEAR;
+use crate::input::Input;
+use crate::inputs::InputSource;
+use crate::solution::Solution;
+use crate::{Error, Result};
+
+/// A day's parsed input, held in memory so that it can be queried with one command after another.
+#[derive(Debug)]
+pub enum Repl {
+    Passports(Vec<PassportInput>),
+    Bags(BagsMap),
+}
+
+impl Repl {
+    /// The days of [`DEFAULT_YEAR`] that have a REPL.
+    pub const DAYS: [usize; 2] = [4, 7];
+
+    /// Reads and parses the day's input, once.
+    pub fn load(year: usize, day: usize, source: &InputSource) -> Result<Self> {
+        if year != DEFAULT_YEAR || !Self::DAYS.contains(&day) {
+            return Err(Error::InvalidInput(format!(
+                "there's no REPL for day {} of {}, only for days {:?} of {}",
+                day,
+                year,
+                Self::DAYS,
+                DEFAULT_YEAR
+            )));
+        }
+        if *source == InputSource::Stdin {
+            return Err(Error::InvalidInput(
+                "the REPL needs an input file, since its commands are read from stdin".into(),
+            ));
+        }
+        let input = Input::from_reader(source.open()?)?;
+        match day {
+            4 => Ok(Self::Passports(Day04.parse(&input)?)),
+            _ => Ok(Self::Bags(Day07.parse(&input)?)),
+        }
+    }
+
+    pub fn help(&self) -> &'static str {
+        match self {
+            Self::Passports(_) => {
+                "passport <n>      checks the nth passport of the input (from 1)\n\
+                 check <fields>    checks a passport, eg: `check byr:1980 iyr:2015 ...`\n\
+                 count             counts the passports, and the valid ones in each part"
+            }
+            Self::Bags(_) => {
+                "containers <bag>  counts the bags that eventually contain the bag, \
+                 eg: `containers muted yellow`\n\
+                 contents <bag>    counts the bags inside the bag\n\
+                 bags              lists every bag's color"
+            }
+        }
+    }
+
+    /// Runs a single command, and returns what to print.
+    pub fn eval(&self, line: &str) -> Result<String> {
+        let line = line.trim();
+        let (command, rest) = match line.find(char::is_whitespace) {
+            Some(i) => (&line[..i], line[i..].trim()),
+            None => (line, ""),
+        };
+        match (self, command) {
+            (_, "help") => Ok(self.help().to_string()),
+            (Self::Passports(passports), "passport") => {
+                let n = rest.parse::<usize>()?;
+                match n.checked_sub(1).and_then(|i| passports.get(i)) {
+                    Some(passport) => Ok(describe(passport)),
+                    None => Err(Error::InvalidInput(format!(
+                        "there are {} passports, numbered from 1",
+                        passports.len()
+                    ))),
+                }
+            }
+            (Self::Passports(_), "check") => Ok(describe(&rest.parse::<PassportInput>()?)),
+            (Self::Passports(passports), "count") => Ok(format!(
+                "{} passports: {} valid in part 1, {} valid in part 2",
+                passports.len(),
+                passports.iter().filter(|p| p.is_valid_part_1()).count(),
+                passports.iter().filter(|p| p.is_valid_part_2()).count()
+            )),
+            (Self::Bags(map), "containers") => {
+                let bag = known_bag(map, rest)?;
+                Ok(format!(
+                    "{} bags eventually contain {}",
+                    map.count_containing_bags(&bag)?,
+                    bag.color()
+                ))
+            }
+            (Self::Bags(map), "contents") => {
+                let bag = known_bag(map, rest)?;
+                Ok(format!(
+                    "{} contains {} bags",
+                    bag.color(),
+                    map.count_item_bags(&bag)?
+                ))
+            }
+            (Self::Bags(map), "bags") => {
+                let mut colors = map.bags().map(Bag::color).collect::<Vec<&str>>();
+                colors.sort_unstable();
+                Ok(colors.join("\n"))
+            }
+            _ => Err(Error::InvalidInput(format!(
+                "unknown command: {} (try help)",
+                command
+            ))),
+        }
+    }
+}
+
+fn describe(passport: &PassportInput) -> String {
+    let problems = passport.problems();
+    match (passport.is_valid_part_1(), passport.is_valid_part_2()) {
+        (_, true) => "valid in parts 1 and 2".to_string(),
+        (true, false) => format!(
+            "valid in part 1, invalid in part 2: {}",
+            problems.join(", ")
+        ),
+        (false, _) => format!("invalid in parts 1 and 2: {}", problems.join(", ")),
+    }
+}
+
+fn known_bag(map: &BagsMap, color: &str) -> Result<Bag> {
+    let bag = Bag::new(color);
+    match map.bags().any(|known| *known == bag) {
+        true => Ok(bag),
+        false => Err(Error::InvalidInput(format!("unknown bag: {}", color))),
+    }
+}
+
+/// Reads commands line by line until `quit` or the end of the input, and writes each result.  A
+/// failing command is reported, and the REPL carries on.
+pub fn run(repl: &Repl, commands: impl BufRead, mut output: impl Write) -> Result<()> {
+    write!(output, "> ")?;
+    output.flush()?;
+    for line in commands.lines() {
+        let line = line?;
+        match line.trim() {
+            "" => {}
+            "quit" | "exit" => break,
+            line => match repl.eval(line) {
+                Ok(result) => writeln!(output, "{}", result)?,
+                Err(err) => writeln!(output, "error: {}", err.chain())?,
+            },
+        }
+        write!(output, "> ")?;
+        output.flush()?;
+    }
+    Ok(())
+}
+
+#[test]
+fn test_repl_bags() -> Result<()> {
+    let repl = Repl::load(
+        2020,
+        7,
+        &InputSource::File("inputs/day_07.example.txt".into()),
+    )?;
+    assert_eq!(
+        repl.eval("containers shiny gold")?,
+        "4 bags eventually contain shiny gold"
+    );
+    assert_eq!(
+        repl.eval("contents shiny gold")?,
+        "shiny gold contains 32 bags"
+    );
+    assert_eq!(
+        repl.eval("  contents   dark olive ")?,
+        "dark olive contains 7 bags"
+    );
+    assert!(repl.eval("bags")?.starts_with("bright white\ndark olive\n"));
+    assert!(repl.eval("contents plaid").is_err());
+    assert!(repl.eval("passport 1").is_err());
+    Ok(())
+}
+
+#[test]
+fn test_repl_passports() -> Result<()> {
+    let repl = Repl::load(
+        2020,
+        4,
+        &InputSource::File("inputs/day_04.example.txt".into()),
+    )?;
+    assert_eq!(
+        repl.eval("count")?,
+        "4 passports: 2 valid in part 1, 2 valid in part 2"
+    );
+    assert_eq!(repl.eval("passport 1")?, "valid in parts 1 and 2");
+    assert_eq!(
+        repl.eval("passport 2")?,
+        "invalid in parts 1 and 2: missing hgt"
+    );
+    assert!(repl.eval("passport 0").is_err());
+    assert!(repl.eval("passport 5").is_err());
+    assert_eq!(
+        repl.eval("check byr:1900 iyr:2015 eyr:2025 hgt:170cm hcl:#123abc ecl:brn pid:000000001")?,
+        "valid in part 1, invalid in part 2: invalid byr: `1900`"
+    );
+
+    assert!(Repl::load(2020, 1, &InputSource::File("inputs/day_01.txt".into())).is_err());
+    assert!(Repl::load(2020, 4, &InputSource::Stdin).is_err());
+    assert!(Repl::load(
+        2019,
+        4,
+        &InputSource::File("inputs/day_04.example.txt".into())
+    )
+    .is_err());
+    Ok(())
+}
+
+#[test]
+fn test_run() -> Result<()> {
+    let repl = Repl::load(
+        2020,
+        7,
+        &InputSource::File("inputs/day_07.example.txt".into()),
+    )?;
+    let mut output = Vec::new();
+    run(
+        &repl,
+        &b"contents shiny gold\n\nnonsense\nquit\ncontents shiny gold\n"[..],
+        &mut output,
+    )?;
+    assert_eq!(
+        String::from_utf8_lossy(&output),
+        "> shiny gold contains 32 bags\n\
+         > > error: Invalid Input Error: `unknown command: nonsense (try help)`\n\
+         > "
+    );
+    Ok(())
+}
@@ -0,0 +1,270 @@
+use rayon::prelude::*;
+use rayon::ThreadPoolBuilder;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::ops::RangeInclusive;
+use std::str::FromStr;
+use std::time::Duration;
+
+use crate::aoc_result::AocReturn;
+use crate::exercises::SOLUTIONS;
+use crate::format::Report;
+use crate::inputs::InputLocator;
+use crate::solution::{Answers, Metadata, Solver};
+use crate::timing::{timed, Timing};
+use crate::{Error, Result};
+
+/// A range of days to run, parsed from either a single day (eg: `3`), or a range (eg: `1..=7`
+/// or `1..8`).
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct DayRange(RangeInclusive<usize>);
+
+impl DayRange {
+    pub fn contains(&self, day: usize) -> bool {
+        self.0.contains(&day)
+    }
+
+    pub fn days(&self) -> RangeInclusive<usize> {
+        self.0.clone()
+    }
+}
+
+impl Default for DayRange {
+    fn default() -> Self {
+        Self(1..=25)
+    }
+}
+
+impl FromStr for DayRange {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let range = if let Some((start, end)) = s.split_once("..=") {
+            start.trim().parse()?..=end.trim().parse()?
+        } else if let Some((start, end)) = s.split_once("..") {
+            let end = end.trim().parse::<usize>()?;
+            start.trim().parse()?
+                ..=end
+                    .checked_sub(1)
+                    .ok_or_else(|| Error::InvalidInput(format!("Invalid day range: {}", s)))?
+        } else {
+            let day = s.trim().parse()?;
+            day..=day
+        };
+        if range.is_empty() {
+            return Err(Error::InvalidInput(format!("Empty day range: {}", s)));
+        }
+        // Like any other day argument, the range's days must be within the advent calendar:
+        let calendar = Self::default();
+        if let Some(&day) = [*range.start(), *range.end()]
+            .iter()
+            .find(|&&day| !calendar.contains(day))
+        {
+            return Err(Error::InvalidDayOrPartArg(day, 1));
+        }
+        Ok(Self(range))
+    }
+}
+
+/// The outcome of running a single part of a day against its conventional input.
+#[derive(Debug)]
+pub struct Run {
+    pub year: usize,
+    pub day: usize,
+    pub part: usize,
+    pub outcome: Result<(AocReturn, Timing)>,
+}
+
+/// Runs both parts of every registered day of the inputs' year within the range, against either
+/// the puzzle input or the example.  A failing part is recorded in its `Run`, and doesn't stop the
+/// remaining days from running.
+///
+/// Each day's input is parsed once, and both parts are answered from it.  The days share no
+/// state, so they are spread across a pool of `jobs` worker threads (or one per cpu, when `jobs` is
+/// 0).  The runs are always returned in order of day, then part.
+pub fn run_all(
+    days: &DayRange,
+    inputs: &InputLocator,
+    example: bool,
+    jobs: usize,
+) -> Result<Summary> {
+    let pool = ThreadPoolBuilder::new().num_threads(jobs).build()?;
+    let solvers = SOLUTIONS
+        .iter()
+        .copied()
+        .filter(|solver| {
+            let metadata = solver.metadata();
+            metadata.year == inputs.year() && days.contains(metadata.day)
+        })
+        .collect::<Vec<&dyn Solver>>();
+
+    let (runs, wall) = timed(|| {
+        pool.install(|| {
+            solvers
+                .into_par_iter()
+                .map(|solver| run_located_input(solver, inputs, example))
+                .collect::<Vec<Vec<Run>>>()
+        })
+    });
+    let runs = runs.into_iter().flatten().collect();
+    Ok(Summary {
+        runs,
+        wall,
+        workers: pool.current_num_threads(),
+    })
+}
+
+fn run_located_input(solver: &dyn Solver, inputs: &InputLocator, example: bool) -> Vec<Run> {
+    let Metadata { year, day, .. } = solver.metadata();
+    let source = inputs.resolve(day, None, example);
+    source
+        .open()
+        .map_err(Error::from)
+        .and_then(|buf_reader| solver.run_both_timed(buf_reader))
+        .unwrap_or_else(|err| Answers::failed(day, err))
+        .into_parts()
+        .map(|(part, outcome)| Run {
+            year,
+            day,
+            part,
+            outcome,
+        })
+        .collect()
+}
+
+/// The runs from `run_all`, which can be displayed as a table.
+#[derive(Debug)]
+pub struct Summary {
+    pub runs: Vec<Run>,
+    /// The wall-clock time to run every part, which is less than the sum of each part's time when
+    /// they ran in parallel.
+    pub wall: Duration,
+    pub workers: usize,
+}
+
+impl Summary {
+    pub fn failures(&self) -> usize {
+        self.runs.iter().filter(|run| run.outcome.is_err()).count()
+    }
+
+    /// The sum of the time spent in each part that succeeded, counting each day's shared parse
+    /// only once.
+    pub fn total(&self) -> Duration {
+        let mut parses = BTreeMap::new();
+        let mut solves = Duration::default();
+        for run in &self.runs {
+            if let Ok((_, timing)) = &run.outcome {
+                parses.insert((run.year, run.day), timing.parse);
+                solves += timing.solve;
+            }
+        }
+        parses.values().sum::<Duration>() + solves
+    }
+
+    pub fn reports(&self) -> Vec<Report> {
+        self.runs
+            .iter()
+            .map(|run| Report::new(run.year, run.day, run.part, &run.outcome))
+            .collect()
+    }
+}
+
+impl fmt::Display for Summary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "year | day | part | {:>12} | {:>12} | answer",
+            "parse", "solve"
+        )?;
+        writeln!(
+            f,
+            "-----+-----+------+--------------+--------------+-------"
+        )?;
+        for run in &self.runs {
+            match &run.outcome {
+                Ok((answer, timing)) => writeln!(
+                    f,
+                    "{:>4} | {:>3} | {:>4} | {:>12} | {:>12} | {}",
+                    run.year,
+                    run.day,
+                    run.part,
+                    format!("{:?}", timing.parse),
+                    format!("{:?}", timing.solve),
+                    answer
+                )?,
+                Err(err) => writeln!(
+                    f,
+                    "{:>4} | {:>3} | {:>4} | {:>12} | {:>12} | error: {}",
+                    run.year,
+                    run.day,
+                    run.part,
+                    "-",
+                    "-",
+                    err.chain()
+                )?,
+            }
+        }
+        writeln!(
+            f,
+            "{} parts in {:?} wall-clock, {:?} across solutions, on {} worker{}",
+            self.runs.len(),
+            self.wall,
+            self.total(),
+            self.workers,
+            if self.workers == 1 { "" } else { "s" }
+        )
+    }
+}
+
+#[test]
+fn test_parse_day_range() -> Result<()> {
+    assert_eq!("3".parse::<DayRange>()?, DayRange(3..=3));
+    assert_eq!("1..=7".parse::<DayRange>()?, DayRange(1..=7));
+    assert_eq!("1..8".parse::<DayRange>()?, DayRange(1..=7));
+    assert!("7..=1".parse::<DayRange>().is_err());
+    assert!("1..1".parse::<DayRange>().is_err());
+    assert!("x..=3".parse::<DayRange>().is_err());
+    assert!(matches!(
+        "0..=99".parse::<DayRange>(),
+        Err(Error::InvalidDayOrPartArg(0, 1))
+    ));
+    assert!(matches!(
+        "30".parse::<DayRange>(),
+        Err(Error::InvalidDayOrPartArg(30, 1))
+    ));
+    assert_eq!("1..26".parse::<DayRange>()?, DayRange(1..=25));
+    Ok(())
+}
+
+#[test]
+fn test_run_all() -> Result<()> {
+    let summary = run_all(&"1..=2".parse()?, &InputLocator::default(), false, 2)?;
+    assert_eq!(summary.failures(), 0);
+    assert_eq!(summary.workers, 2);
+
+    // Each day's input is parsed once, for both parts:
+    let (_, first) = summary.runs[0].outcome.as_ref().unwrap();
+    let (_, second) = summary.runs[1].outcome.as_ref().unwrap();
+    let (_, third) = summary.runs[2].outcome.as_ref().unwrap();
+    let (_, fourth) = summary.runs[3].outcome.as_ref().unwrap();
+    assert_eq!(first.parse, second.parse);
+    assert_eq!(third.parse, fourth.parse);
+    assert_eq!(
+        summary.total(),
+        first.total() + second.solve + third.total() + fourth.solve
+    );
+    assert_eq!(
+        summary
+            .runs
+            .into_iter()
+            .map(|run| Ok((run.year, run.day, run.part, run.outcome?.0)))
+            .collect::<Result<Vec<_>>>()?,
+        vec![
+            (2020, 1, 1, Some(876459_u32).into()),
+            (2020, 1, 2, Some(116168640_u32).into()),
+            (2020, 2, 1, 550_usize.into()),
+            (2020, 2, 2, 634_usize.into()),
+        ]
+    );
+    Ok(())
+}
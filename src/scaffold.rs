@@ -0,0 +1,258 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::exercises::DEFAULT_YEAR;
+use crate::inputs::InputLocator;
+use crate::{Error, Result};
+
+const SOLUTION_TEMPLATE: &str = include_str!("scaffold/solution.rs.template");
+
+/// Generates the module for a new day from a template, and wires it into `exercises.rs` and its
+/// `SOLUTIONS` registry.  Empty input files are created too, if they don't exist yet.  The day is
+/// for the input locator's year: another year than the default year gets its own `year_YYYY`
+/// module, which is created on its first day.
+///
+/// Refuses to touch a day that already exists.  Returns the paths that were created.
+pub fn scaffold(
+    root: &Path,
+    inputs: &InputLocator,
+    day: usize,
+    title: &str,
+) -> Result<Vec<PathBuf>> {
+    let year = inputs.year();
+    if year < 2015 {
+        return Err(Error::InvalidYearArg(year));
+    }
+    if !(1..=25).contains(&day) {
+        return Err(Error::InvalidDayOrPartArg(day, 1));
+    }
+    let exercises_path = root.join("src/exercises.rs");
+    let mut exercises = fs::read_to_string(&exercises_path)?;
+    // Another year's days are declared in the year's module, and registered through it:
+    let year_module = match year {
+        DEFAULT_YEAR => None,
+        year => Some((
+            format!("year_{}", year),
+            root.join(format!("src/exercises/year_{}.rs", year)),
+        )),
+    };
+    let modules_dir = match &year_module {
+        Some((name, _)) => root.join("src/exercises").join(name),
+        None => root.join("src/exercises"),
+    };
+    // A missing year module is created, so it doesn't declare anything yet:
+    let declarations = match &year_module {
+        Some((_, path)) if path.exists() => Some(fs::read_to_string(path)?),
+        Some(_) => None,
+        None => Some(exercises.clone()),
+    };
+    let module = format!("pub mod day_{:02};", day);
+    let module_path = modules_dir.join(format!("day_{:02}.rs", day));
+    let module_dir = modules_dir.join(format!("day_{:02}", day));
+    if module_path.exists()
+        || module_dir.exists()
+        || declarations
+            .as_ref()
+            .is_some_and(|declarations| declarations.contains(&module))
+    {
+        return Err(Error::InvalidInput(format!(
+            "Day {} of {} already exists",
+            day, year
+        )));
+    }
+
+    let mut created = vec![];
+    let (entry, year_file) = match &year_module {
+        Some((name, path)) => {
+            let declarations = match declarations {
+                Some(declarations) => register_module(&declarations, module)?,
+                None => {
+                    exercises = register_module(&exercises, format!("pub mod {};", name))?;
+                    created.push(path.clone());
+                    module + "\n"
+                }
+            };
+            (
+                format!("    &{}::day_{:02}::Day{:02},", name, day, day),
+                Some((path, declarations)),
+            )
+        }
+        None => {
+            exercises = register_module(&exercises, module)?;
+            (format!("    &day_{:02}::Day{:02},", day, day), None)
+        }
+    };
+    let exercises = register_solution(&exercises, entry)?;
+
+    let solution = SOLUTION_TEMPLATE
+        .replace("__DD__", &format!("{:02}", day))
+        .replace("__DAY__", &day.to_string())
+        .replace("__YEAR__", &year.to_string())
+        .replace("__TITLE__", &title.replace('"', "\\\""))
+        .replace("__INPUT__", &inputs.puzzle_input(day).display().to_string())
+        .replace(
+            "__EXAMPLE__",
+            &inputs.example_input(day).display().to_string(),
+        );
+    fs::create_dir_all(&module_dir)?;
+    fs::write(
+        &module_path,
+        format!("pub use solution::Day{:02};\n\nmod solution;\n", day),
+    )?;
+    fs::write(module_dir.join("solution.rs"), solution)?;
+    fs::write(&exercises_path, exercises)?;
+    if let Some((path, declarations)) = year_file {
+        fs::write(path, declarations)?;
+    }
+
+    created.extend(vec![module_path, module_dir.join("solution.rs")]);
+    for input in &[inputs.puzzle_input(day), inputs.example_input(day)] {
+        let input = root.join(input);
+        if !input.exists() {
+            if let Some(dir) = input.parent() {
+                fs::create_dir_all(dir)?;
+            }
+            fs::write(&input, "")?;
+            created.push(input);
+        }
+    }
+    Ok(created)
+}
+
+/// Adds a `pub mod` declaration among the existing ones.  The default year's days sort before the
+/// other years' modules.
+fn register_module(source: &str, module: String) -> Result<String> {
+    let mut lines = source.lines().map(String::from).collect::<Vec<String>>();
+    insert_sorted(&mut lines, module, "pub mod ")?;
+    Ok(lines.join("\n") + "\n")
+}
+
+/// Adds an entry to the `SOLUTIONS` registry, after the entries of any earlier day or year.
+fn register_solution(exercises: &str, entry: String) -> Result<String> {
+    let mut lines = exercises.lines().map(String::from).collect::<Vec<String>>();
+    insert_sorted(&mut lines, entry, "    &")?;
+    Ok(lines.join("\n") + "\n")
+}
+
+/// Inserts the line among the existing lines with the same prefix, which are zero-padded by day so
+/// that they sort lexicographically.
+fn insert_sorted(lines: &mut Vec<String>, line: String, prefix: &str) -> Result<()> {
+    let siblings = lines
+        .iter()
+        .enumerate()
+        .filter(|(_, existing)| existing.starts_with(prefix))
+        .map(|(i, _)| i)
+        .collect::<Vec<usize>>();
+    let index = siblings
+        .iter()
+        .copied()
+        .find(|&i| lines[i] > line)
+        .or_else(|| siblings.last().map(|i| i + 1))
+        .ok_or_else(|| Error::InvalidState(format!("unable to find where to insert: {}", line)))?;
+    lines.insert(index, line);
+    Ok(())
+}
+
+/// The day after the year's last implemented day.
+pub fn next_day(year: usize) -> usize {
+    crate::implemented_days()
+        .filter(|metadata| metadata.year == year)
+        .map(|metadata| metadata.day)
+        .max()
+        .unwrap_or(0)
+        + 1
+}
+
+#[test]
+fn test_register() -> Result<()> {
+    let register = |exercises: &str, day: usize| -> Result<String> {
+        let exercises = register_module(exercises, format!("pub mod day_{:02};", day))?;
+        register_solution(&exercises, format!("    &day_{:02}::Day{:02},", day, day))
+    };
+    let exercises = "\
+use crate::solution::Solver;
+
+pub mod day_01;
+pub mod day_03;
+
+pub static SOLUTIONS: &[&dyn Solver] = &[
+    &day_01::Day01,
+    &day_03::Day03,
+];
+";
+    assert_eq!(
+        register(exercises, 2)?,
+        "\
+use crate::solution::Solver;
+
+pub mod day_01;
+pub mod day_02;
+pub mod day_03;
+
+pub static SOLUTIONS: &[&dyn Solver] = &[
+    &day_01::Day01,
+    &day_02::Day02,
+    &day_03::Day03,
+];
+"
+    );
+    assert!(register(exercises, 4)?.contains("pub mod day_03;\npub mod day_04;\n"));
+    assert!(register(exercises, 4)?.contains("&day_03::Day03,\n    &day_04::Day04,\n"));
+    Ok(())
+}
+
+#[test]
+fn test_scaffold() -> Result<()> {
+    let root = std::env::temp_dir().join(format!("aoc_scaffold_{}", std::process::id()));
+    fs::create_dir_all(root.join("src/exercises"))?;
+    fs::write(
+        root.join("src/exercises.rs"),
+        "pub mod day_07;\n\npub static SOLUTIONS: &[&dyn Solver] = &[\n    &day_07::Day07,\n];\n",
+    )?;
+    let inputs = InputLocator::new("inputs");
+
+    let created = scaffold(&root, &inputs, 8, "Handheld Halting")?;
+    assert_eq!(created.len(), 4);
+    let solution = fs::read_to_string(root.join("src/exercises/day_08/solution.rs"))?;
+    assert!(solution.contains("pub struct Day08;"));
+    assert!(solution.contains("title: \"Handheld Halting\""));
+    assert!(solution.contains("\"inputs/day_08.example.txt\""));
+    let exercises = fs::read_to_string(root.join("src/exercises.rs"))?;
+    assert!(exercises.contains("pub mod day_07;\npub mod day_08;\n"));
+    assert!(exercises.contains("&day_07::Day07,\n    &day_08::Day08,\n"));
+
+    // The day now exists, so it must not be overwritten:
+    assert!(scaffold(&root, &inputs, 8, "Handheld Halting").is_err());
+    assert_eq!(
+        fs::read_to_string(root.join("src/exercises/day_08/solution.rs"))?,
+        solution
+    );
+
+    // Another year gets its own module, which is created on its first day:
+    let inputs = inputs.with_year(2021);
+    let created = scaffold(&root, &inputs, 1, "Report Repair")?;
+    assert_eq!(created.len(), 5);
+    assert_eq!(
+        fs::read_to_string(root.join("src/exercises/year_2021.rs"))?,
+        "pub mod day_01;\n"
+    );
+    let solution = fs::read_to_string(root.join("src/exercises/year_2021/day_01/solution.rs"))?;
+    assert!(solution.contains("year: 2021,"));
+    assert!(solution.contains("\"inputs/2021/day_01.txt\""));
+    assert!(root.join("inputs/2021/day_01.example.txt").is_file());
+    scaffold(&root, &inputs, 3, "Toboggan Trajectory")?;
+    assert_eq!(
+        fs::read_to_string(root.join("src/exercises/year_2021.rs"))?,
+        "pub mod day_01;\npub mod day_03;\n"
+    );
+    let exercises = fs::read_to_string(root.join("src/exercises.rs"))?;
+    assert!(exercises.starts_with("pub mod day_07;\npub mod day_08;\npub mod year_2021;\n"));
+    assert!(exercises.contains(
+        "&day_08::Day08,\n    &year_2021::day_01::Day01,\n    &year_2021::day_03::Day03,\n"
+    ));
+    assert!(scaffold(&root, &inputs, 3, "Toboggan Trajectory").is_err());
+    assert!(scaffold(&root, &inputs.with_year(2014), 1, "Too Early").is_err());
+
+    fs::remove_dir_all(&root)?;
+    Ok(())
+}
@@ -0,0 +1,139 @@
+use std::io::{BufReader, Read};
+use std::time::Duration;
+
+use crate::aoc_result::AocReturn;
+use crate::input::Input;
+use crate::timing::{timed, Timing};
+use crate::{Error, Result};
+
+/// Describes a registered day, so that the cli can list what has been implemented so far.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Metadata {
+    /// The AoC year (eg: 2020).
+    pub year: usize,
+    /// The AoC day (1-25).
+    pub day: usize,
+    /// The title of the day's puzzle.
+    pub title: &'static str,
+}
+
+/// Every day implements this trait, which splits the work into parsing the input into the day's
+/// domain model, and then answering each part of the puzzle from that model.
+pub trait Solution {
+    type Model;
+    type Part1: Into<AocReturn>;
+    type Part2: Into<AocReturn>;
+
+    fn metadata(&self) -> Metadata;
+    fn parse(&self, input: &Input) -> Result<Self::Model>;
+    fn part_1(&self, model: &Self::Model) -> Result<Self::Part1>;
+    fn part_2(&self, model: &Self::Model) -> Result<Self::Part2>;
+}
+
+/// The answers to both parts of a day, solved from a single parse of its input.  Each part's
+/// `Timing` includes the shared parse time.
+#[derive(Debug)]
+pub struct Answers {
+    pub part_1: Result<(AocReturn, Timing)>,
+    pub part_2: Result<(AocReturn, Timing)>,
+}
+
+impl Answers {
+    /// Both parts fail when the input can't be parsed, but the error itself can only be reported
+    /// once, so part 2 refers back to part 1.
+    pub fn failed(day: usize, err: Error) -> Self {
+        Self {
+            part_1: Err(err),
+            part_2: Err(Error::InvalidInput(format!(
+                "unable to parse the input for day {}, see part 1",
+                day
+            ))),
+        }
+    }
+
+    /// Each part's number, along with its outcome.
+    pub fn into_parts(self) -> impl Iterator<Item = (usize, Result<(AocReturn, Timing)>)> {
+        vec![(1, self.part_1), (2, self.part_2)].into_iter()
+    }
+}
+
+/// Because each `Solution` has its own input and answer types, it can't be stored in the
+/// registry directly.  This trait erases those types, and is implemented for every `Solution`.
+pub trait Solver: Sync {
+    fn metadata(&self) -> Metadata;
+    fn run_timed(
+        &self,
+        part: usize,
+        buf_reader: BufReader<Box<dyn Read + '_>>,
+    ) -> Result<(AocReturn, Timing)>;
+
+    /// Parses the input once, and answers both parts from it.  Only a failure to read or parse the
+    /// input fails the whole run.
+    fn run_both_timed(&self, buf_reader: BufReader<Box<dyn Read + '_>>) -> Result<Answers>;
+
+    fn run(&self, part: usize, buf_reader: BufReader<Box<dyn Read + '_>>) -> Result<AocReturn> {
+        Ok(self.run_timed(part, buf_reader)?.0)
+    }
+}
+
+impl<S> Solver for S
+where
+    S: Solution + Sync,
+{
+    fn metadata(&self) -> Metadata {
+        Solution::metadata(self)
+    }
+
+    fn run_timed(
+        &self,
+        part: usize,
+        buf_reader: BufReader<Box<dyn Read + '_>>,
+    ) -> Result<(AocReturn, Timing)> {
+        if !(1..=2).contains(&part) {
+            return Err(Error::InvalidDayOrPartArg(
+                Solution::metadata(self).day,
+                part,
+            ));
+        }
+        let (model, parse) = parse_timed(self, buf_reader)?;
+        solve_timed(self, &model, part, parse)
+    }
+
+    fn run_both_timed(&self, buf_reader: BufReader<Box<dyn Read + '_>>) -> Result<Answers> {
+        let (model, parse) = parse_timed(self, buf_reader)?;
+        Ok(Answers {
+            part_1: solve_timed(self, &model, 1, parse),
+            part_2: solve_timed(self, &model, 2, parse),
+        })
+    }
+}
+
+fn parse_timed<S: Solution>(
+    solution: &S,
+    buf_reader: BufReader<Box<dyn Read + '_>>,
+) -> Result<(S::Model, Duration)> {
+    let day = solution.metadata().day;
+    let _span = tracing::debug_span!("parse", day).entered();
+    // Reading the input counts towards the parse phase:
+    let (model, parse) = timed(|| solution.parse(&Input::from_reader(buf_reader)?));
+    tracing::debug!(elapsed = ?parse, ok = model.is_ok(), "parsed the input");
+    Ok((model?, parse))
+}
+
+fn solve_timed<S: Solution>(
+    solution: &S,
+    model: &S::Model,
+    part: usize,
+    parse: Duration,
+) -> Result<(AocReturn, Timing)> {
+    let day = solution.metadata().day;
+    let _span = tracing::debug_span!("solve", day, part).entered();
+    let (answer, solve) = timed(|| -> Result<AocReturn> {
+        match part {
+            1 => Ok(solution.part_1(model)?.into()),
+            _ => Ok(solution.part_2(model)?.into()),
+        }
+    });
+    tracing::debug!(elapsed = ?solve, ok = answer.is_ok(), "solved the part");
+    Ok((answer?, Timing { parse, solve }))
+}
@@ -0,0 +1,277 @@
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::time::Duration;
+
+use crate::client::Client;
+#[cfg(test)]
+use crate::client::{MockServer, Session};
+use crate::history::{now, History, Record};
+use crate::{Error, Result};
+
+/// What the website made of a submitted answer.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Feedback {
+    Correct,
+    TooHigh,
+    TooLow,
+    /// Wrong, without a hint about which way.
+    Incorrect,
+    /// An answer was submitted too recently, so this one wasn't checked.
+    Wait(Duration),
+    /// The part was already solved, so this answer wasn't checked.
+    AlreadySolved,
+}
+
+impl Feedback {
+    /// Parses the response page, which describes the feedback in its `<article>`.
+    pub fn parse(page: &str) -> Result<Self> {
+        lazy_static! {
+            static ref ARTICLE: Regex = Regex::new(r"(?s)<article[^>]*>(.*?)</article>").unwrap();
+            static ref TAG: Regex = Regex::new(r"<[^>]*>").unwrap();
+            static ref WAIT: Regex = Regex::new(
+                r"You have (?:(?P<minutes>\d+)m\s*)?(?:(?P<seconds>\d+)s\s*)?left to wait"
+            )
+            .unwrap();
+        }
+        let article = ARTICLE
+            .captures(page)
+            .map_or(page, |caps| caps.get(1).map_or(page, |m| m.as_str()));
+        let text = TAG.replace_all(article, "");
+
+        if text.contains("That's the right answer") {
+            Ok(Self::Correct)
+        } else if text.contains("That's not the right answer") {
+            Ok(if text.contains("your answer is too high") {
+                Self::TooHigh
+            } else if text.contains("your answer is too low") {
+                Self::TooLow
+            } else {
+                Self::Incorrect
+            })
+        } else if text.contains("You gave an answer too recently") {
+            let caps = WAIT.captures(&text);
+            let unit = |name: &str| {
+                caps.as_ref()
+                    .and_then(|caps| caps.name(name))
+                    .map_or(Ok(0), |m| m.as_str().parse::<u64>())
+            };
+            Ok(Self::Wait(Duration::from_secs(
+                unit("minutes")? * 60 + unit("seconds")?,
+            )))
+        } else if text.contains("Did you already complete it") {
+            Ok(Self::AlreadySolved)
+        } else {
+            Err(Error::InvalidInput(format!(
+                "unrecognized response to the answer: {}",
+                text.trim()
+            )))
+        }
+    }
+
+    /// Whether the website judged the answer to be wrong.
+    pub fn is_wrong(&self) -> bool {
+        matches!(self, Self::TooHigh | Self::TooLow | Self::Incorrect)
+    }
+}
+
+impl fmt::Display for Feedback {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Correct => write!(f, "correct"),
+            Self::TooHigh => write!(f, "too high"),
+            Self::TooLow => write!(f, "too low"),
+            Self::Incorrect => write!(f, "incorrect"),
+            Self::Wait(wait) => write!(f, "wait {}s before submitting again", wait.as_secs()),
+            Self::AlreadySolved => write!(f, "already solved"),
+        }
+    }
+}
+
+/// Submits the answer for the day's part, unless it's already known to be wrong: either it was
+/// judged wrong before, or it's outside the bounds from earlier too high and too low feedback.  An
+/// answer isn't submitted either while the website's last request to wait for the year is running.
+/// Feedback that judges the answer or asks to wait is recorded in the history; an already solved
+/// part isn't.
+pub fn submit(
+    client: &Client,
+    history: &mut History,
+    year: usize,
+    day: usize,
+    part: usize,
+    answer: &str,
+    input_hash: Option<String>,
+) -> Result<Feedback> {
+    if !(1..=25).contains(&day) || !(1..=2).contains(&part) {
+        return Err(Error::InvalidDayOrPartArg(day, part));
+    }
+    if let Some(rejected) = history.rejection(year, day, part, answer, input_hash.as_deref()) {
+        return Err(Error::InvalidInput(format!(
+            "`{}` was already submitted for day {}, part {}, and was {}",
+            answer,
+            day,
+            part,
+            rejected
+                .feedback
+                .as_ref()
+                .map_or("wrong".into(), |f| f.to_string())
+        )));
+    }
+    if let Some(reason) = history
+        .bounds(year, day, part, input_hash.as_deref())
+        .check(answer)
+    {
+        return Err(Error::InvalidInput(format!(
+            "`{}` can't be the answer for day {}, part {}: {}",
+            answer, day, part, reason
+        )));
+    }
+    if let Some(wait) = history.wait(year, now()) {
+        return Err(Error::InvalidInput(format!(
+            "an answer for {} was submitted too recently, wait {}s before submitting again",
+            year,
+            wait.as_secs()
+        )));
+    }
+    let feedback = Feedback::parse(&client.answer(year, day, part, answer)?)?;
+    if feedback != Feedback::AlreadySolved {
+        history.record(Record {
+            feedback: Some(feedback.clone()),
+            ..Record::new(year, day, part, answer, input_hash)
+        });
+    }
+    Ok(feedback)
+}
+
+#[cfg(test)]
+fn page(article: &str) -> String {
+    format!(
+        "<!DOCTYPE html>\n<html><body><main>\n<article><p>{}</p></article>\n</main></body></html>",
+        article
+    )
+}
+
+#[test]
+fn test_parse_feedback() -> Result<()> {
+    let cases = vec![
+        (
+            "That's the right answer!  You are <span class=\"day-success\">one gold star</span> closer.",
+            Feedback::Correct,
+        ),
+        (
+            "That's not the right answer; your answer is too high.  If you're stuck, ...",
+            Feedback::TooHigh,
+        ),
+        (
+            "That's not the right answer; your answer is too low.",
+            Feedback::TooLow,
+        ),
+        (
+            "That's not the right answer.  If you're stuck, make sure you're using the full input data.",
+            Feedback::Incorrect,
+        ),
+        (
+            "You gave an answer too recently; you have to wait after submitting an answer before trying again.  You have 37s left to wait.",
+            Feedback::Wait(Duration::from_secs(37)),
+        ),
+        (
+            "You gave an answer too recently; you have to wait after submitting an answer before trying again.  You have 1m 5s left to wait.",
+            Feedback::Wait(Duration::from_secs(65)),
+        ),
+        (
+            "You don't seem to be solving the right level.  Did you already complete it?",
+            Feedback::AlreadySolved,
+        ),
+    ];
+    for (article, feedback) in cases {
+        assert_eq!(Feedback::parse(&page(article))?, feedback, "{}", article);
+    }
+    assert!(Feedback::parse(&page("Something else entirely.")).is_err());
+    Ok(())
+}
+
+#[test]
+fn test_submit() -> Result<()> {
+    let server = MockServer::start(vec![
+        (
+            200,
+            &page("That's not the right answer; your answer is too high."),
+        ),
+        (200, &page("That's the right answer!")),
+    ]);
+    let client = Client::new(&server.url, Some(Session::new("abc123")));
+    let mut history = History::default();
+    let hash = || Some("abc".to_string());
+
+    assert_eq!(
+        submit(&client, &mut history, 2020, 1, 2, "999999999", hash())?,
+        Feedback::TooHigh
+    );
+    // Known wrong answers are refused without asking the website again:
+    let err = submit(&client, &mut history, 2020, 1, 2, "999999999", hash()).unwrap_err();
+    assert!(err.to_string().contains("too high"), "{}", err);
+    let err = submit(&client, &mut history, 2020, 1, 2, "1000000000", hash()).unwrap_err();
+    assert!(err.to_string().contains("< 999999999"), "{}", err);
+    assert_eq!(
+        submit(&client, &mut history, 2020, 1, 2, "116168640", hash())?,
+        Feedback::Correct
+    );
+
+    let received = server.received();
+    assert_eq!(received.len(), 2);
+    assert_eq!(received[0].method, "POST");
+    assert_eq!(received[0].url, "/2020/day/1/answer");
+    assert_eq!(received[0].header("Cookie"), Some("session=abc123"));
+    assert_eq!(received[0].body, "level=2&answer=999999999");
+    assert_eq!(received[1].body, "level=2&answer=116168640");
+    assert_eq!(
+        history
+            .iter()
+            .map(|record| (record.answer.as_str(), record.feedback.clone()))
+            .collect::<Vec<_>>(),
+        vec![
+            ("999999999", Some(Feedback::TooHigh)),
+            ("116168640", Some(Feedback::Correct))
+        ]
+    );
+    Ok(())
+}
+
+#[test]
+fn test_submit_waits() -> Result<()> {
+    let server = MockServer::start(vec![
+        (
+            200,
+            &page("You gave an answer too recently.  You have 1m 5s left to wait."),
+        ),
+        (200, &page("That's the right answer!")),
+    ]);
+    let client = Client::new(&server.url, Some(Session::new("abc123")));
+    let mut history = History::default();
+
+    assert_eq!(
+        submit(&client, &mut history, 2020, 1, 1, "876459", None)?,
+        Feedback::Wait(Duration::from_secs(65))
+    );
+    // The website isn't asked again until the wait is over, whichever part of the year it's for:
+    let err = submit(&client, &mut history, 2020, 2, 1, "550", None).unwrap_err();
+    assert!(
+        err.to_string().contains("submitted too recently"),
+        "{}",
+        err
+    );
+    assert!(history.wait(2020, now()).is_some());
+
+    // Another year has its own wait:
+    assert_eq!(
+        submit(&client, &mut history, 2019, 1, 1, "1", None)?,
+        Feedback::Correct
+    );
+
+    let received = server.received();
+    assert_eq!(received.len(), 2);
+    assert_eq!(received[0].url, "/2020/day/1/answer");
+    assert_eq!(received[1].url, "/2019/day/1/answer");
+    Ok(())
+}
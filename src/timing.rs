@@ -0,0 +1,39 @@
+use serde::ser::{Serialize, SerializeStruct, Serializer};
+use std::fmt;
+use std::time::{Duration, Instant};
+
+/// How long a solution took, split into the input read/parse phase and the solve phase.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct Timing {
+    pub parse: Duration,
+    pub solve: Duration,
+}
+
+impl Timing {
+    pub fn total(&self) -> Duration {
+        self.parse + self.solve
+    }
+}
+
+impl fmt::Display for Timing {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "parse: {:?}, solve: {:?}", self.parse, self.solve)
+    }
+}
+
+/// Serialized as nanoseconds, so that scripts don't have to parse the units.
+impl Serialize for Timing {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut state = serializer.serialize_struct("Timing", 2)?;
+        state.serialize_field("parse_ns", &(self.parse.as_nanos() as u64))?;
+        state.serialize_field("solve_ns", &(self.solve.as_nanos() as u64))?;
+        state.end()
+    }
+}
+
+/// Runs the closure, returning its result along with how long it took.
+pub fn timed<T>(f: impl FnOnce() -> T) -> (T, Duration) {
+    let start = Instant::now();
+    let res = f();
+    (res, start.elapsed())
+}
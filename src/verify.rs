@@ -0,0 +1,218 @@
+use serde::Deserialize;
+use std::fmt;
+use std::fs::{self, File};
+use std::io::{BufReader, Read};
+use std::path::{Path, PathBuf};
+
+use crate::exercises::default_year;
+use crate::{Error, Result};
+
+/// The known answers, loaded from a TOML file with one `[[answer]]` table per entry.
+#[derive(Debug, Deserialize)]
+pub struct Manifest {
+    #[serde(rename = "answer", default)]
+    pub answers: Vec<Entry>,
+}
+
+impl Manifest {
+    pub fn from_path(path: &Path) -> Result<Self> {
+        let load = || Ok(toml::from_str(&fs::read_to_string(path)?)?);
+        load().map_err(|err: Error| err.in_file(path))
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq)]
+pub struct Entry {
+    /// Entries without a year are from 2020.
+    #[serde(default = "default_year")]
+    pub year: usize,
+    pub day: usize,
+    pub part: usize,
+    pub input: PathBuf,
+    pub expected: Expected,
+}
+
+/// Expected answers can be written as TOML integers or strings, and are compared against the
+/// answer's `Display` form.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq)]
+#[serde(untagged)]
+pub enum Expected {
+    Integer(i64),
+    Text(String),
+}
+
+impl fmt::Display for Expected {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Integer(expected) => write!(f, "{}", expected),
+            Self::Text(expected) => write!(f, "{}", expected),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum Verdict {
+    Pass,
+    Mismatch {
+        expected: String,
+        actual: String,
+    },
+    /// The solution couldn't produce an answer at all.
+    Fail(Error),
+}
+
+#[derive(Debug)]
+pub struct Verification {
+    pub entry: Entry,
+    pub verdict: Verdict,
+}
+
+/// Runs every entry in the manifest.  A failing entry doesn't stop the remaining entries.
+pub fn verify(manifest: &Manifest) -> Verifications {
+    Verifications(
+        manifest
+            .answers
+            .iter()
+            .map(|entry| Verification {
+                entry: entry.clone(),
+                verdict: match run_entry(entry) {
+                    Ok(actual) if actual == entry.expected.to_string() => Verdict::Pass,
+                    Ok(actual) => Verdict::Mismatch {
+                        expected: entry.expected.to_string(),
+                        actual,
+                    },
+                    Err(err) => Verdict::Fail(err),
+                },
+            })
+            .collect(),
+    )
+}
+
+fn run_entry(entry: &Entry) -> Result<String> {
+    let reader: Box<dyn Read> = Box::new(File::open(&entry.input)?);
+    Ok(crate::aoc(entry.year, entry.day, entry.part, BufReader::new(reader))?.to_string())
+}
+
+#[derive(Debug)]
+pub struct Verifications(pub Vec<Verification>);
+
+impl Verifications {
+    /// The number of entries that either mismatched or failed.
+    pub fn failures(&self) -> usize {
+        self.0
+            .iter()
+            .filter(|verification| !matches!(verification.verdict, Verdict::Pass))
+            .count()
+    }
+}
+
+impl fmt::Display for Verifications {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for Verification { entry, verdict } in &self.0 {
+            write!(
+                f,
+                "day {:>2}, part {} ({}): ",
+                entry.day,
+                entry.part,
+                entry.input.display()
+            )?;
+            match verdict {
+                Verdict::Pass => writeln!(f, "pass")?,
+                Verdict::Mismatch { expected, actual } => writeln!(
+                    f,
+                    "mismatch\n    - expected: {}\n    + actual:   {}",
+                    expected, actual
+                )?,
+                Verdict::Fail(err) => writeln!(f, "fail: {}", err.chain())?,
+            }
+        }
+        writeln!(
+            f,
+            "{} passed, {} failed",
+            self.0.len() - self.failures(),
+            self.failures()
+        )
+    }
+}
+
+#[test]
+fn test_parse_manifest() -> Result<()> {
+    let manifest: Manifest = toml::from_str(
+        r#"
+        [[answer]]
+        day = 1
+        part = 2
+        input = "inputs/day_01.example.txt"
+        expected = 241861950
+
+        [[answer]]
+        day = 3
+        part = 1
+        input = "inputs/day_03.example.txt"
+        expected = "7"
+        "#,
+    )?;
+    assert_eq!(
+        manifest.answers,
+        vec![
+            Entry {
+                year: 2020,
+                day: 1,
+                part: 2,
+                input: "inputs/day_01.example.txt".into(),
+                expected: Expected::Integer(241861950),
+            },
+            Entry {
+                year: 2020,
+                day: 3,
+                part: 1,
+                input: "inputs/day_03.example.txt".into(),
+                expected: Expected::Text("7".into()),
+            },
+        ]
+    );
+    Ok(())
+}
+
+#[test]
+fn test_verify() -> Result<()> {
+    let entry = |day, input: &str, expected| Entry {
+        year: 2020,
+        day,
+        part: 1,
+        input: input.into(),
+        expected: Expected::Integer(expected),
+    };
+    let verifications = verify(&Manifest {
+        answers: vec![
+            entry(1, "inputs/day_01.example.txt", 514579),
+            entry(1, "inputs/day_01.example.txt", 1),
+            entry(1, "inputs/missing.txt", 514579),
+        ],
+    });
+    assert_eq!(verifications.failures(), 2);
+    assert!(matches!(verifications.0[0].verdict, Verdict::Pass));
+    assert!(matches!(
+        &verifications.0[1].verdict,
+        Verdict::Mismatch { expected, actual } if expected == "1" && actual == "514579"
+    ));
+    assert!(matches!(verifications.0[2].verdict, Verdict::Fail(_)));
+    Ok(())
+}
+
+#[test]
+fn test_verify_manifest() -> Result<()> {
+    let verifications = verify(&Manifest::from_path(Path::new("answers.toml"))?);
+    assert_eq!(verifications.failures(), 0, "{}", verifications);
+    Ok(())
+}
+
+#[test]
+fn test_manifest_from_path_error() {
+    let path = Path::new("no_such_answers.toml");
+    let err = Manifest::from_path(path).unwrap_err();
+    assert!(err
+        .chain()
+        .to_string()
+        .starts_with("Error in `no_such_answers.toml`: IO Error"));
+}
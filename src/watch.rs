@@ -0,0 +1,184 @@
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use crate::aoc_result::AocReturn;
+use crate::inputs::InputSource;
+use crate::solution::Answers;
+use crate::timing::Timing;
+use crate::verify::Manifest;
+use crate::Result;
+
+/// Polls files for changes, by their modification time and length.  A file that's missing is
+/// watched until it appears.
+#[derive(Debug)]
+pub struct Watcher {
+    files: Vec<(PathBuf, Option<(SystemTime, u64)>)>,
+}
+
+impl Watcher {
+    pub fn new(paths: impl IntoIterator<Item = PathBuf>) -> Self {
+        Self {
+            files: paths.into_iter().map(|path| (path, None)).collect(),
+        }
+    }
+
+    /// Whether any file changed since the last poll.  The first poll counts as a change, as long
+    /// as one of the files exists.
+    pub fn changed(&mut self) -> bool {
+        let mut changed = false;
+        for (path, stamp) in &mut self.files {
+            let current = fs::metadata(&path)
+                .and_then(|metadata| Ok((metadata.modified()?, metadata.len())))
+                .ok();
+            changed |= current != *stamp;
+            *stamp = current;
+        }
+        changed
+    }
+}
+
+/// A part's answer, checked against the manifest's expected answer for the same input, if any.
+#[derive(Debug)]
+pub struct Check {
+    pub day: usize,
+    pub part: usize,
+    pub outcome: Result<(AocReturn, Timing)>,
+    pub expected: Option<String>,
+}
+
+impl Check {
+    /// Whether the answer is known to be right.  Without an expected answer, this can't be known.
+    pub fn matches(&self) -> Option<bool> {
+        match (&self.outcome, &self.expected) {
+            (Ok((answer, _)), Some(expected)) => Some(answer.to_string() == *expected),
+            (Err(_), Some(_)) => Some(false),
+            (_, None) => None,
+        }
+    }
+}
+
+impl fmt::Display for Check {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "day {}, part {}: ", self.day, self.part)?;
+        let (answer, timing) = match &self.outcome {
+            Ok(answer) => answer,
+            Err(err) => return write!(f, "error: {}", err.chain()),
+        };
+        write!(f, "{} (took {:?}, {})", answer, timing.total(), timing)?;
+        match (self.matches(), &self.expected) {
+            (Some(true), _) => write!(f, ", matches the expected answer"),
+            (_, Some(expected)) => write!(f, ", but {} was expected", expected),
+            (_, None) => Ok(()),
+        }
+    }
+}
+
+/// Answers the day's part (or both parts) from the input, and checks the answers against the
+/// manifest's entries for the same input.  Stdin has no entries.
+pub fn check(
+    year: usize,
+    day: usize,
+    part: Option<usize>,
+    source: &InputSource,
+    manifest: Option<&Manifest>,
+) -> Result<Vec<Check>> {
+    let outcomes = match part {
+        Some(part) => vec![(part, crate::aoc_timed(year, day, part, source.open()?))],
+        None => crate::aoc_both_timed(year, day, source.open()?)
+            .unwrap_or_else(|err| Answers::failed(day, err))
+            .into_parts()
+            .collect(),
+    };
+    Ok(outcomes
+        .into_iter()
+        .map(|(part, outcome)| Check {
+            day,
+            part,
+            outcome,
+            expected: match (manifest, source) {
+                (Some(manifest), InputSource::File(path)) => {
+                    expected(manifest, year, day, part, path)
+                }
+                _ => None,
+            },
+        })
+        .collect())
+}
+
+fn expected(
+    manifest: &Manifest,
+    year: usize,
+    day: usize,
+    part: usize,
+    input: &Path,
+) -> Option<String> {
+    manifest
+        .answers
+        .iter()
+        .find(|entry| {
+            (entry.year, entry.day, entry.part) == (year, day, part) && entry.input == input
+        })
+        .map(|entry| entry.expected.to_string())
+}
+
+#[test]
+fn test_watcher() -> Result<()> {
+    let path = std::env::temp_dir().join(format!("aoc_watch_{}.txt", std::process::id()));
+    let mut watcher = Watcher::new(vec![path.clone()]);
+    assert!(!watcher.changed());
+
+    fs::write(&path, "1721\n")?;
+    assert!(watcher.changed());
+    assert!(!watcher.changed());
+    fs::write(&path, "1721\n979\n")?;
+    assert!(watcher.changed());
+
+    fs::remove_file(&path)?;
+    assert!(watcher.changed());
+    Ok(())
+}
+
+#[test]
+fn test_check() -> Result<()> {
+    use crate::verify::{Entry, Expected};
+
+    let input = PathBuf::from("inputs/day_01.example.txt");
+    let manifest = Manifest {
+        answers: vec![
+            Entry {
+                year: 2020,
+                day: 1,
+                part: 1,
+                input: input.clone(),
+                expected: Expected::Integer(514579),
+            },
+            Entry {
+                year: 2020,
+                day: 1,
+                part: 2,
+                input: input.clone(),
+                expected: Expected::Integer(1),
+            },
+        ],
+    };
+    let source = InputSource::File(input);
+    let checks = check(2020, 1, None, &source, Some(&manifest))?;
+    assert_eq!(
+        checks.iter().map(Check::matches).collect::<Vec<_>>(),
+        vec![Some(true), Some(false)]
+    );
+    assert!(checks[0]
+        .to_string()
+        .ends_with(", matches the expected answer"));
+    assert!(checks[1]
+        .to_string()
+        .starts_with("day 1, part 2: 241861950 (took "));
+    assert!(checks[1].to_string().ends_with(", but 1 was expected"));
+
+    let checks = check(2020, 1, Some(1), &source, None)?;
+    assert_eq!(checks.len(), 1);
+    assert_eq!(checks[0].matches(), None);
+    Ok(())
+}